@@ -3,7 +3,7 @@
 //! Run with: cargo bench
 
 use d2o::{
-    BashGenerator, Command, ElvishGenerator, FishGenerator, JsonGenerator, Layout,
+    BashGenerator, Cache, Command, ElvishGenerator, FishGenerator, JsonGenerator, Layout,
     NushellGenerator, Opt, OptName, OptNameType, Postprocessor, ZshGenerator,
 };
 use divan::AllocProfiler;
@@ -77,6 +77,11 @@ fn sample_help_large() -> String {
     lines.join("\n")
 }
 
+/// Same shape as `sample_help_medium`, with non-ASCII text in every description
+fn sample_help_medium_mixed() -> String {
+    sample_help_medium().replace("Description", "Déscription →")
+}
+
 fn sample_command_small() -> Command {
     Command {
         name: EcoString::from("mycmd"),
@@ -90,6 +95,10 @@ fn sample_command_small() -> Command {
                 ],
                 argument: EcoString::new(),
                 description: EcoString::from("Print help"),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
             },
             Opt {
                 names: eco_vec![
@@ -98,10 +107,16 @@ fn sample_command_small() -> Command {
                 ],
                 argument: EcoString::new(),
                 description: EcoString::from("Verbose output"),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
             },
         ],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::from("1.0.0"),
+        metadata: None,
     }
 }
 
@@ -118,6 +133,10 @@ fn sample_command_medium() -> Command {
                 EcoString::new()
             },
             description: EcoString::from(format!("Option number {}", i)),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         })
         .collect();
 
@@ -127,8 +146,10 @@ fn sample_command_medium() -> Command {
             description: EcoString::from(format!("Subcommand {}", i)),
             usage: EcoString::new(),
             options: eco_vec![],
+            positionals: eco_vec![],
             subcommands: eco_vec![],
             version: EcoString::new(),
+            metadata: None,
         })
         .collect();
 
@@ -137,8 +158,10 @@ fn sample_command_medium() -> Command {
         description: EcoString::from("A medium-sized command"),
         usage: EcoString::from("mediumcmd [OPTIONS] [COMMAND]"),
         options,
+        positionals: EcoVec::new(),
         subcommands,
         version: EcoString::from("2.0.0"),
+        metadata: None,
     }
 }
 
@@ -164,6 +187,10 @@ fn sample_command_large() -> Command {
                 "This is the description for option number {}",
                 i
             )),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         })
         .collect();
 
@@ -172,8 +199,10 @@ fn sample_command_large() -> Command {
         description: EcoString::from("A large command with many options"),
         usage: EcoString::from("largecmd [OPTIONS]"),
         options,
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::from("3.0.0"),
+        metadata: None,
     }
 }
 
@@ -223,6 +252,18 @@ fn preprocess_blockwise_medium(bencher: Bencher) {
     bencher.bench_local(|| Layout::preprocess_blockwise(black_box(&help)));
 }
 
+#[divan::bench]
+fn parse_blockwise_medium_ascii(bencher: Bencher) {
+    let help = sample_help_medium();
+    bencher.bench_local(|| Layout::parse_blockwise(black_box(&help)));
+}
+
+#[divan::bench]
+fn parse_blockwise_medium_mixed(bencher: Bencher) {
+    let help = sample_help_medium_mixed();
+    bencher.bench_local(|| Layout::parse_blockwise(black_box(&help)));
+}
+
 // ============================================================================
 // Generator benchmarks
 // ============================================================================
@@ -470,6 +511,10 @@ fn sample_command_massive() -> Command {
                 "This is the description for option number {} with additional context",
                 i
             )),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         })
         .collect();
 
@@ -478,8 +523,10 @@ fn sample_command_massive() -> Command {
         description: EcoString::from("A massive command with thousands of options"),
         usage: EcoString::from("massivecmd [OPTIONS]"),
         options,
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::from("1.0.0"),
+        metadata: None,
     }
 }
 
@@ -495,6 +542,18 @@ fn parse_blockwise_10mb(bencher: Bencher) {
     bencher.bench_local(|| Layout::parse_blockwise(black_box(&help)));
 }
 
+#[divan::bench]
+fn hash_content_fnv_massive(bencher: Bencher) {
+    let help = sample_help_massive();
+    bencher.bench_local(|| Cache::hash_content_fnv(black_box(&help)));
+}
+
+#[divan::bench]
+fn hash_content_fast_massive(bencher: Bencher) {
+    let help = sample_help_massive();
+    bencher.bench_local(|| Cache::hash_content_fast(black_box(&help)));
+}
+
 #[divan::bench]
 fn preprocess_blockwise_massive(bencher: Bencher) {
     let help = sample_help_massive();
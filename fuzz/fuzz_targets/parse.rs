@@ -0,0 +1,59 @@
+//! Feed arbitrary bytes through the parsing pipeline and every generator.
+//!
+//! Run with: cargo +nightly fuzz run parse
+
+#![no_main]
+
+use d2o::{
+    BashGenerator, Command, DescriptionMode, ElvishGenerator, FishGenerator, GeneratorOptions,
+    IoHandler, JsonGenerator, Layout, MdocParser, NushellGenerator, Postprocessor,
+    PowerShellGenerator, SubcommandParser, ZshGenerator,
+};
+use ecow::EcoString;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // Same normalization chain as `d2o --file`
+    let content = Postprocessor::convert_tabs_to_spaces(text, 8);
+    let content = Postprocessor::unicode_spaces_to_ascii(&Postprocessor::remove_bullets(
+        &IoHandler::normalize_text(&content),
+    ));
+
+    let mut cmd = Command::new(EcoString::from("fuzz"));
+    cmd.options = Layout::parse_blockwise(&content);
+    cmd.usage = Layout::parse_usage(&content);
+    for sub in SubcommandParser::parse(&content).iter() {
+        let mut subcmd = Command::new(sub.cmd.clone());
+        subcmd.description = sub.desc.clone();
+        subcmd.options = MdocParser::parse(&content);
+        cmd.subcommands.push(subcmd);
+    }
+    let cmd = Postprocessor::ascii_only(Postprocessor::fix_command(cmd));
+
+    for description_mode in [
+        DescriptionMode::Full,
+        DescriptionMode::FirstSentence,
+        DescriptionMode::None,
+    ] {
+        let options = GeneratorOptions {
+            bash_completion_compat: true,
+            json_typed: true,
+            max_desc_len: Some(data.len() % 16),
+            description_mode,
+            bundle_shorts: true,
+            zsh_compdef_name: Some(EcoString::from("fuzz")),
+            ..Default::default()
+        };
+        FishGenerator::generate_with_options(&cmd, &options);
+        ZshGenerator::generate_with_options(&cmd, &options);
+        BashGenerator::generate_with_options(&cmd, &options);
+        ElvishGenerator::generate_with_options(&cmd, &options);
+        NushellGenerator::generate_with_options(&cmd, &options);
+        PowerShellGenerator::generate_with_options(&cmd, &options);
+        JsonGenerator::generate_with_options(&cmd, &options);
+    }
+});
@@ -16,9 +16,22 @@ use tracing::{debug, trace, warn};
 /// Default TTL for cache entries (24 hours in seconds)
 pub const DEFAULT_TTL_SECS: u64 = 24 * 60 * 60;
 
+/// Layout version of the cache entries this build writes. Bump it when a
+/// change to `CacheEntry` or `Command` needs older entries rewritten, and add
+/// the upgrade step to [`Cache::migrate_schema`].
+pub const CACHE_SCHEMA_VERSION: u32 = 2;
+
+/// Entries written before `schema_version` existed are version 1.
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 /// A cached Command with metadata for TTL validation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry {
+    /// Layout version the entry was written with
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     /// Unix timestamp when this entry was created
     pub created_at: u64,
     /// Hash of the input content (help text) for validation
@@ -27,29 +40,35 @@ pub struct CacheEntry {
     pub command: Command,
 }
 
+/// Current Unix timestamp in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 impl CacheEntry {
     /// Create a new cache entry with the current timestamp.
     pub fn new(command: Command, content_hash: u64) -> Self {
-        let created_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-
         Self {
-            created_at,
+            schema_version: CACHE_SCHEMA_VERSION,
+            created_at: unix_now(),
             content_hash,
             command,
         }
     }
 
+    /// Parse a stored entry, upgrading it first if an older build wrote it.
+    pub fn from_json(data: &str) -> Result<Self> {
+        let value = serde_json::from_str(data).context("Failed to parse cache entry")?;
+        let value = Cache::migrate_schema(value)?;
+        serde_json::from_value(value).context("Failed to deserialize cache entry")
+    }
+
     /// Check if this cache entry is still valid (not expired).
     pub fn is_valid(&self, ttl_secs: u64) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-
-        let age = now.saturating_sub(self.created_at);
+        let age = unix_now().saturating_sub(self.created_at);
         age < ttl_secs
     }
 
@@ -94,12 +113,51 @@ impl Cache {
         Ok(cache_dir)
     }
 
+    /// Suffix naming the [`Cache::hash_content`] algorithm, so entries hashed
+    /// by another build never match by accident. Empty for the default
+    /// FNV-1a, keeping existing keys unchanged. foldhash may hash differently
+    /// in a new release, so its tag names the version and must be updated
+    /// whenever the foldhash dependency is.
+    #[cfg(feature = "fast-hash")]
+    const CONTENT_HASH_TAG: &str = "~foldhash-0.2.0";
+    #[cfg(not(feature = "fast-hash"))]
+    const CONTENT_HASH_TAG: &str = "";
+
     /// Generate a cache key from a command name and optional source identifier.
     fn cache_key(name: &str, source: Option<&str>) -> EcoString {
         let sanitized_name = name.replace(['/', '\\', ':'], "_");
         match source {
-            Some(s) => EcoString::from(format!("{}_{:016x}", sanitized_name, Self::hash_string(s))),
-            None => EcoString::from(sanitized_name),
+            Some(s) => EcoString::from(format!(
+                "{}_{:016x}{}",
+                sanitized_name,
+                Self::hash_string(s),
+                Self::CONTENT_HASH_TAG
+            )),
+            None => EcoString::from(format!("{}{}", sanitized_name, Self::CONTENT_HASH_TAG)),
+        }
+    }
+
+    /// Generate a cache key that also namespaces by tool version.
+    ///
+    /// An empty or missing version yields the unversioned key so entries
+    /// written before the version was known stay reachable.
+    fn versioned_key(name: &str, source: Option<&str>, version: Option<&str>) -> EcoString {
+        let key = Self::cache_key(name, source);
+        match version.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(v) => {
+                let sanitized: String = v
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+') {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+                EcoString::from(format!("{}@{}", key, sanitized))
+            }
+            None => key,
         }
     }
 
@@ -114,10 +172,36 @@ impl Cache {
     }
 
     /// Hash content for cache validation.
+    ///
+    /// FNV-1a by default; with the `fast-hash` feature, foldhash, which is
+    /// much faster on very large help texts. The feature only selects the
+    /// algorithm, which is also recorded in the cache key, so entries from
+    /// a build using the other one are never compared. Cache keys
+    /// themselves always use FNV-1a.
     pub fn hash_content(content: &str) -> u64 {
+        #[cfg(feature = "fast-hash")]
+        {
+            Self::hash_content_fast(content)
+        }
+        #[cfg(not(feature = "fast-hash"))]
+        {
+            Self::hash_content_fnv(content)
+        }
+    }
+
+    /// FNV-1a content hash, the default for [`Cache::hash_content`].
+    pub fn hash_content_fnv(content: &str) -> u64 {
         Self::hash_string(content)
     }
 
+    /// foldhash content hash with a fixed seed, used for
+    /// [`Cache::hash_content`] under the `fast-hash` feature.
+    pub fn hash_content_fast(content: &str) -> u64 {
+        use std::hash::BuildHasher;
+
+        foldhash::quality::FixedState::with_seed(0).hash_one(content)
+    }
+
     /// Get the path to a cache file for a given key.
     fn cache_path(&self, key: &str) -> PathBuf {
         self.cache_dir.join(format!("{}.json", key))
@@ -133,7 +217,19 @@ impl Cache {
         source: Option<&str>,
         content_hash: u64,
     ) -> Option<Command> {
-        let key = Self::cache_key(name, source);
+        self.get_versioned(name, source, None, content_hash).await
+    }
+
+    /// Like [`Cache::get`], but looks up the entry stored for a specific
+    /// tool version so `foo 1.0` and `foo 2.0` never shadow each other.
+    pub async fn get_versioned(
+        &self,
+        name: &str,
+        source: Option<&str>,
+        version: Option<&str>,
+        content_hash: u64,
+    ) -> Option<Command> {
+        let key = Self::versioned_key(name, source, version);
         let path = self.cache_path(&key);
 
         trace!("Looking for cache entry at: {}", path.display());
@@ -146,10 +242,10 @@ impl Cache {
             }
         };
 
-        let entry: CacheEntry = match serde_json::from_str(&data) {
+        let entry = match CacheEntry::from_json(&data) {
             Ok(entry) => entry,
             Err(e) => {
-                warn!("Cache entry corrupted, removing: {}", e);
+                warn!("Cache entry corrupted, removing: {:#}", e);
                 let _ = tokio::fs::remove_file(&path).await;
                 return None;
             }
@@ -178,7 +274,20 @@ impl Cache {
         content_hash: u64,
         command: &Command,
     ) -> Result<()> {
-        let key = Self::cache_key(name, source);
+        self.set_versioned(name, source, None, content_hash, command)
+            .await
+    }
+
+    /// Like [`Cache::set`], but stores the entry under a specific tool version.
+    pub async fn set_versioned(
+        &self,
+        name: &str,
+        source: Option<&str>,
+        version: Option<&str>,
+        content_hash: u64,
+        command: &Command,
+    ) -> Result<()> {
+        let key = Self::versioned_key(name, source, version);
         let path = self.cache_path(&key);
 
         let entry = CacheEntry::new(command.clone(), content_hash);
@@ -193,6 +302,75 @@ impl Cache {
         Ok(())
     }
 
+    /// Reset the TTL of a still-valid entry without re-parsing.
+    ///
+    /// Returns `true` if the entry was refreshed, `false` if it is missing,
+    /// unreadable, or already expired.
+    pub async fn touch(
+        &self,
+        name: &str,
+        source: Option<&str>,
+        version: Option<&str>,
+    ) -> Result<bool> {
+        let key = Self::versioned_key(name, source, version);
+        let path = self.cache_path(&key);
+
+        let Ok(data) = tokio::fs::read_to_string(&path).await else {
+            return Ok(false);
+        };
+        let Ok(mut entry) = CacheEntry::from_json(&data) else {
+            return Ok(false);
+        };
+        if !entry.is_valid(self.ttl.as_secs()) {
+            return Ok(false);
+        }
+
+        entry.created_at = unix_now();
+        let data =
+            serde_json::to_string_pretty(&entry).context("Failed to serialize cache entry")?;
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("Failed to write cache entry: {}", path.display()))?;
+
+        trace!("Refreshed cache entry: {}", path.display());
+        Ok(true)
+    }
+
+    /// Upgrade a raw entry to [`CACHE_SCHEMA_VERSION`] one version at a
+    /// time, so entries from older builds are kept instead of being removed
+    /// as corrupted. Entries from newer builds are rejected.
+    pub fn migrate_schema(mut entry: serde_json::Value) -> Result<serde_json::Value> {
+        let version = entry
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(u64::from(legacy_schema_version()));
+        if version > u64::from(CACHE_SCHEMA_VERSION) {
+            anyhow::bail!(
+                "Cache entry schema v{} is newer than supported v{}",
+                version,
+                CACHE_SCHEMA_VERSION
+            );
+        }
+
+        for from in version..u64::from(CACHE_SCHEMA_VERSION) {
+            match from {
+                // Fields added to `Opt` and `Command` since v1 all have serde
+                // defaults, so only the version needs recording
+                1 => {}
+                _ => anyhow::bail!("No migration from cache schema v{}", from),
+            }
+        }
+
+        entry
+            .as_object_mut()
+            .context("Cache entry is not a JSON object")?
+            .insert(
+                "schema_version".to_string(),
+                serde_json::Value::from(CACHE_SCHEMA_VERSION),
+            );
+        Ok(entry)
+    }
+
     /// Clear all cache entries.
     pub async fn clear(&self) -> Result<usize> {
         let mut count = 0;
@@ -216,7 +394,7 @@ impl Cache {
             let path = entry.path();
             if path.extension().is_some_and(|ext| ext == "json")
                 && let Ok(data) = tokio::fs::read_to_string(&path).await
-                && let Ok(cache_entry) = serde_json::from_str::<CacheEntry>(&data)
+                && let Ok(cache_entry) = CacheEntry::from_json(&data)
                 && !cache_entry.is_valid(self.ttl.as_secs())
             {
                 tokio::fs::remove_file(&path).await?;
@@ -243,7 +421,7 @@ impl Cache {
                     total_size += metadata.len();
                 }
                 if let Ok(data) = tokio::fs::read_to_string(&path).await
-                    && let Ok(cache_entry) = serde_json::from_str::<CacheEntry>(&data)
+                    && let Ok(cache_entry) = CacheEntry::from_json(&data)
                 {
                     if cache_entry.is_valid(self.ttl.as_secs()) {
                         valid += 1;
@@ -262,6 +440,41 @@ impl Cache {
             cache_dir: self.cache_dir.clone(),
         })
     }
+
+    /// Describe every readable cache entry, sorted by key.
+    pub async fn list(&self) -> Result<Vec<CacheEntryInfo>> {
+        let mut infos = Vec::new();
+
+        let mut entries = tokio::fs::read_dir(&self.cache_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let Ok(data) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let Ok(cache_entry) = CacheEntry::from_json(&data) else {
+                continue;
+            };
+
+            infos.push(CacheEntryInfo {
+                key: EcoString::from(key),
+                name: cache_entry.command.name.clone(),
+                created_at: cache_entry.created_at,
+                size_bytes: data.len() as u64,
+                valid: cache_entry.is_valid(self.ttl.as_secs()),
+                options: cache_entry.command.total_option_count(),
+                subcommands: cache_entry.command.total_subcommand_count(),
+            });
+        }
+
+        infos.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(infos)
+    }
 }
 
 impl Default for Cache {
@@ -280,6 +493,31 @@ pub struct CacheStats {
     pub cache_dir: PathBuf,
 }
 
+/// Metadata about one cache entry, as reported by [`Cache::list`].
+#[derive(Debug, Clone)]
+pub struct CacheEntryInfo {
+    /// File name of the entry without its extension
+    pub key: EcoString,
+    /// Name of the cached command
+    pub name: EcoString,
+    /// Unix timestamp when the entry was created or last refreshed
+    pub created_at: u64,
+    pub size_bytes: u64,
+    /// Whether the entry is still within the TTL
+    pub valid: bool,
+    /// Options on the command and all its subcommands
+    pub options: usize,
+    /// Subcommands at every depth
+    pub subcommands: usize,
+}
+
+impl CacheEntryInfo {
+    /// Seconds since the entry was created.
+    pub fn age_secs(&self) -> u64 {
+        unix_now().saturating_sub(self.created_at)
+    }
+}
+
 impl std::fmt::Display for CacheStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -333,7 +571,7 @@ mod tests {
     #[test]
     fn test_cache_key_generation() {
         let key1 = Cache::cache_key("git", None);
-        assert_eq!(key1.as_str(), "git");
+        assert_eq!(key1.as_str(), format!("git{}", Cache::CONTENT_HASH_TAG));
 
         let key2 = Cache::cache_key("git", Some("--help"));
         assert!(key2.starts_with("git_"));
@@ -371,6 +609,127 @@ mod tests {
         assert_eq!(cached.description.as_str(), "My command");
     }
 
+    #[test]
+    fn test_versioned_key() {
+        let foo = Cache::cache_key("foo", None);
+        assert_eq!(Cache::versioned_key("foo", None, None), foo);
+        assert_eq!(Cache::versioned_key("foo", None, Some(" ")), foo);
+        assert_eq!(
+            Cache::versioned_key("foo", None, Some("2.0 (beta)")).as_str(),
+            format!("{}@2.0__beta_", foo)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_versions_do_not_collide() {
+        let (cache, _temp) = test_cache(3600);
+
+        let mut v1 = Command::new(EcoString::from("foo"));
+        v1.version = EcoString::from("1.0");
+        v1.description = EcoString::from("old");
+        let mut v2 = Command::new(EcoString::from("foo"));
+        v2.version = EcoString::from("2.0");
+        v2.description = EcoString::from("new");
+
+        cache
+            .set_versioned("foo", None, Some("1.0"), 1, &v1)
+            .await
+            .expect("set v1");
+        cache
+            .set_versioned("foo", None, Some("2.0"), 1, &v2)
+            .await
+            .expect("set v2");
+
+        let got1 = cache.get_versioned("foo", None, Some("1.0"), 1).await;
+        let got2 = cache.get_versioned("foo", None, Some("2.0"), 1).await;
+        assert_eq!(got1.expect("v1 cached").description.as_str(), "old");
+        assert_eq!(got2.expect("v2 cached").description.as_str(), "new");
+
+        // The unversioned slot is untouched
+        assert!(cache.get("foo", None, 1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_list_reports_entries_and_validity() {
+        let (cache, temp) = test_cache(3600);
+
+        cache
+            .set(
+                "fresh",
+                Some("--help"),
+                1,
+                &Command::new(EcoString::from("fresh")),
+            )
+            .await
+            .expect("set fresh");
+        let mut cmd = Command::new(EcoString::from("stale"));
+        let mut sub = Command::new(EcoString::from("run"));
+        sub.options
+            .push(crate::types::Opt::from_help_line("  -q  Quiet").unwrap());
+        cmd.subcommands.push(sub);
+        let mut stale = CacheEntry::new(cmd, 2);
+        stale.created_at = 0;
+        std::fs::write(
+            temp.path().join("stale.json"),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .expect("write stale entry");
+
+        let list = cache.list().await.expect("list");
+        assert_eq!(list.len(), 2);
+        let fresh = list.iter().find(|e| e.name == "fresh").expect("fresh");
+        assert!(fresh.valid);
+        assert!(fresh.key.starts_with("fresh_"));
+        assert!(fresh.size_bytes > 0);
+        let stale = list.iter().find(|e| e.name == "stale").expect("stale");
+        assert!(!stale.valid);
+        assert_eq!(stale.key.as_str(), "stale");
+        assert_eq!((stale.options, stale.subcommands), (1, 1));
+        assert_eq!((fresh.options, fresh.subcommands), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_v1_entry_migrates_instead_of_being_dropped() {
+        let (cache, _temp) = test_cache(3600);
+
+        // Written before `schema_version` and the newer `Opt` fields existed
+        let v1 = format!(
+            r#"{{"created_at":{},"content_hash":7,"command":{{"name":"old","description":"","usage":"","options":[{{"names":["-v"],"argument":"","description":"Verbose"}}]}}}}"#,
+            unix_now()
+        );
+        let entry = CacheEntry::from_json(&v1).expect("migrate v1 entry");
+        assert_eq!(entry.schema_version, CACHE_SCHEMA_VERSION);
+        let opt = &entry.command.options[0];
+        assert!(!opt.multiple && !opt.toggle && !opt.optional_argument);
+        assert!(opt.value_range.is_none());
+        assert!(entry.command.subcommands.is_empty());
+
+        let path = cache.cache_path(&Cache::cache_key("old", None));
+        std::fs::write(&path, &v1).expect("write v1 entry");
+        let cached = cache.get("old", None, 7).await.expect("v1 entry is a hit");
+        assert_eq!(cached.options[0].description.as_str(), "Verbose");
+        assert!(path.exists());
+
+        let future = v1.replacen('{', r#"{"schema_version":99,"#, 1);
+        assert!(CacheEntry::from_json(&future).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v0_entry_is_removed_as_corrupted() {
+        let (cache, _temp) = test_cache(3600);
+
+        let v0 = format!(
+            r#"{{"schema_version":0,"created_at":{},"content_hash":7,"command":{{"name":"old","description":"","usage":"","options":[]}}}}"#,
+            unix_now()
+        );
+        assert!(CacheEntry::from_json(&v0).is_err());
+
+        let path = cache.cache_path(&Cache::cache_key("old", None));
+        std::fs::write(&path, &v0).expect("write v0 entry");
+        assert!(cache.get("old", None, 7).await.is_none());
+        assert!(!path.exists());
+    }
+
     #[tokio::test]
     async fn test_cache_miss_on_content_change() {
         let (cache, _temp) = test_cache(3600);
@@ -409,6 +768,42 @@ mod tests {
         assert!(cache.get("mycmd", None, hash).await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_touch_extends_validity() {
+        let (cache, temp) = test_cache(120);
+
+        let cmd = Command::new(EcoString::from("mycmd"));
+        cache.set("mycmd", None, 7, &cmd).await.expect("cache set");
+
+        // Age the entry to 100 seconds
+        let path = cache.cache_path(&Cache::cache_key("mycmd", None));
+        let mut entry: CacheEntry =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        entry.created_at -= 100;
+        std::fs::write(&path, serde_json::to_string(&entry).unwrap()).unwrap();
+
+        // A shorter TTL would consider the aged entry expired...
+        let short_ttl = Cache {
+            cache_dir: temp.path().to_path_buf(),
+            ttl: Duration::from_secs(30),
+        };
+        let aged: CacheEntry =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(!aged.is_valid(30));
+
+        // ...but touching it resets the clock
+        assert!(cache.touch("mycmd", None, None).await.expect("touch"));
+        assert!(short_ttl.get("mycmd", None, 7).await.is_some());
+
+        // Missing entries are not touched
+        assert!(
+            !cache
+                .touch("missing", None, None)
+                .await
+                .expect("touch missing")
+        );
+    }
+
     #[tokio::test]
     async fn test_cache_clear() {
         let (cache, _temp) = test_cache(3600);
@@ -449,6 +844,39 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_hash_content_consistent_per_algorithm() {
+        let content = "Usage: tool [OPTIONS]\n  -v, --verbose  Verbose output";
+        assert_eq!(
+            Cache::hash_content_fnv(content),
+            Cache::hash_content_fnv(content)
+        );
+        assert_eq!(
+            Cache::hash_content_fast(content),
+            Cache::hash_content_fast(content)
+        );
+        assert_ne!(
+            Cache::hash_content_fast(content),
+            Cache::hash_content_fast("Usage: tool")
+        );
+        // The default stays FNV-1a so existing entries keep matching
+        #[cfg(not(feature = "fast-hash"))]
+        assert_eq!(
+            Cache::hash_content(content),
+            Cache::hash_content_fnv(content)
+        );
+    }
+
+    #[test]
+    fn test_cache_key_records_content_hash() {
+        let key = Cache::cache_key("foo", Some("--help"));
+        #[cfg(feature = "fast-hash")]
+        assert!(key.ends_with("~foldhash-0.2.0"));
+        #[cfg(not(feature = "fast-hash"))]
+        assert!(!key.contains('~'));
+        assert!(Cache::versioned_key("foo", None, Some("1.0")).ends_with("@1.0"));
+    }
+
     #[test]
     fn test_hash_content_different() {
         let hash1 = Cache::hash_content("content a");
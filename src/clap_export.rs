@@ -0,0 +1,163 @@
+use crate::types::{Command, Opt, OptNameType};
+use clap::{Arg, ArgAction};
+use std::collections::HashSet;
+
+impl Command {
+    /// Build a `clap::Command` from the parsed command, so downstream tools
+    /// can reuse clap's help rendering, completion and validation.
+    ///
+    /// Options map to `clap::Arg`s with their long and short names, value
+    /// name and help, and subcommands are converted recursively. Names clap
+    /// cannot express (`-name`, `/flag`, `+feature`) are left out, and an
+    /// option without any expressible name is skipped. clap's own `--help`
+    /// and `--version` flags are disabled so parsed ones take their place.
+    pub fn as_clap_command(&self) -> clap::Command {
+        let mut command = clap::Command::new(self.name.to_string())
+            .disable_help_flag(true)
+            .disable_version_flag(true);
+
+        if !self.description.is_empty() {
+            command = command.about(self.description.to_string());
+        }
+        if !self.usage.is_empty() {
+            command = command.override_usage(self.usage.to_string());
+        }
+        if !self.version.is_empty() {
+            command = command.version(self.version.to_string());
+        }
+
+        // clap rejects repeated ids and names, so later duplicates are dropped
+        let mut seen = HashSet::new();
+        for opt in self.options.iter() {
+            if let Some(arg) = Self::opt_to_arg(opt, &mut seen) {
+                command = command.arg(arg);
+            }
+        }
+
+        let mut subcommand_names = HashSet::new();
+        for sub in self.subcommands.iter() {
+            if subcommand_names.insert(sub.name.as_str()) {
+                command = command.subcommand(sub.as_clap_command());
+            }
+        }
+
+        command
+    }
+
+    fn opt_to_arg(opt: &Opt, seen: &mut HashSet<String>) -> Option<Arg> {
+        let mut longs = Vec::new();
+        let mut shorts = Vec::new();
+
+        for name in opt.names.iter() {
+            match name.opt_type {
+                OptNameType::LongType => {
+                    let long = name.raw.trim_start_matches('-');
+                    if !long.is_empty() && seen.insert(format!("--{}", long)) {
+                        longs.push(long.to_string());
+                    }
+                }
+                OptNameType::ShortType => {
+                    if let Some(short) = name.raw.chars().nth(1)
+                        && seen.insert(format!("-{}", short))
+                    {
+                        shorts.push(short);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let id = match (longs.first(), shorts.first()) {
+            (Some(long), _) => long.clone(),
+            (None, Some(short)) => short.to_string(),
+            (None, None) => return None,
+        };
+        if !seen.insert(format!("id:{}", id)) {
+            return None;
+        }
+
+        let mut arg = Arg::new(id);
+        let mut longs = longs.into_iter();
+        if let Some(long) = longs.next() {
+            arg = arg.long(long);
+        }
+        arg = arg.visible_aliases(longs);
+        let mut shorts = shorts.into_iter();
+        if let Some(short) = shorts.next() {
+            arg = arg.short(short);
+        }
+        arg = arg.visible_short_aliases(shorts);
+
+        if !opt.description.is_empty() {
+            arg = arg.help(opt.description.to_string());
+        }
+
+        if opt.argument.is_empty() {
+            arg = arg.action(if opt.multiple {
+                ArgAction::Count
+            } else {
+                ArgAction::SetTrue
+            });
+        } else {
+            let value_name = opt.argument.trim_start_matches(['<', '[']);
+            let value_name = value_name.trim_end_matches(['>', ']']);
+            arg = arg
+                .value_name(value_name.to_string())
+                .action(if opt.multiple {
+                    ArgAction::Append
+                } else {
+                    ArgAction::Set
+                });
+            if opt.optional_argument {
+                arg = arg.num_args(0..=1).require_equals(true);
+            }
+        }
+
+        Some(arg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Layout;
+    use crate::types::Command;
+    use ecow::EcoString;
+
+    #[test]
+    fn test_as_clap_command_from_parsed_help() {
+        let help = "\
+  -v, --verbose        Print more output
+  -o, --output <FILE>  Write to FILE
+  -h, --help           Show help
+";
+        let mut cmd = Command::new(EcoString::from("tool"));
+        cmd.options = Layout::parse_blockwise(help);
+        let mut sub = Command::new(EcoString::from("run"));
+        sub.description = EcoString::from("Run it");
+        sub.options = Layout::parse_blockwise("  -n, --dry-run      Do nothing\n");
+        cmd.subcommands.push(sub);
+
+        let clap_cmd = cmd.as_clap_command();
+        clap_cmd.clone().debug_assert();
+
+        assert_eq!(clap_cmd.get_arguments().count(), cmd.options.len());
+        let output = clap_cmd
+            .get_arguments()
+            .find(|a| a.get_long() == Some("output"))
+            .expect("output arg");
+        assert_eq!(output.get_short(), Some('o'));
+        assert_eq!(output.get_value_names().unwrap()[0].as_str(), "FILE");
+
+        let run = clap_cmd.find_subcommand("run").expect("run subcommand");
+        assert_eq!(run.get_arguments().count(), 1);
+
+        let matches = clap_cmd
+            .try_get_matches_from(["tool", "-v", "--output", "out.txt", "run", "-n"])
+            .expect("parsed args");
+        assert!(matches.get_flag("verbose"));
+        assert_eq!(
+            matches.get_one::<String>("output").map(String::as_str),
+            Some("out.txt")
+        );
+    }
+}
@@ -4,6 +4,9 @@ use clap_verbosity_flag::Verbosity;
 /// Default cache TTL in hours (24 hours)
 pub const DEFAULT_CACHE_TTL_HOURS: u64 = 24;
 
+/// Default number of spaces a tab expands to
+pub const DEFAULT_TAB_WIDTH: usize = 8;
+
 #[derive(ValueEnum, Clone, Debug, Copy)]
 pub enum Shell {
     /// Bash shell completion
@@ -21,7 +24,129 @@ pub enum Shell {
     Nushell,
 }
 
-#[derive(Parser, Debug)]
+impl Shell {
+    /// Name of the matching `--format` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Fish => "fish",
+            Shell::Zsh => "zsh",
+            Shell::PowerShell => "powershell",
+            Shell::Elvish => "elvish",
+            Shell::Nushell => "nushell",
+        }
+    }
+}
+
+/// Value of `--self-completions`: one shell, or every shell at once
+#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+pub enum CompletionsTarget {
+    /// Bash shell completion
+    Bash,
+    /// Fish shell completion
+    Fish,
+    /// Zsh shell completion
+    Zsh,
+    /// PowerShell completion
+    #[value(name = "powershell")]
+    PowerShell,
+    /// Elvish shell completion
+    Elvish,
+    /// Nushell completion
+    Nushell,
+    /// d2o's own completion for every shell, in labeled sections
+    All,
+}
+
+impl CompletionsTarget {
+    /// The shells this target covers, in output order
+    pub fn shells(&self) -> &'static [Shell] {
+        match self {
+            CompletionsTarget::Bash => &[Shell::Bash],
+            CompletionsTarget::Fish => &[Shell::Fish],
+            CompletionsTarget::Zsh => &[Shell::Zsh],
+            CompletionsTarget::PowerShell => &[Shell::PowerShell],
+            CompletionsTarget::Elvish => &[Shell::Elvish],
+            CompletionsTarget::Nushell => &[Shell::Nushell],
+            CompletionsTarget::All => &[
+                Shell::Bash,
+                Shell::Zsh,
+                Shell::Fish,
+                Shell::PowerShell,
+                Shell::Elvish,
+                Shell::Nushell,
+            ],
+        }
+    }
+
+    /// The single shell this target names, or `None` for `all`
+    pub fn shell(&self) -> Option<Shell> {
+        match self.shells() {
+            [shell] => Some(*shell),
+            _ => None,
+        }
+    }
+}
+
+/// Markup of the input text
+#[derive(ValueEnum, Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Plain help or rendered man page text
+    #[default]
+    Text,
+    /// Man page source (roff, including BSD mdoc macros)
+    Man,
+    /// Markdown, such as a README, with usage in fenced code blocks
+    Markdown,
+    /// PowerShell help, with parameters written as `-Name:<Type>`
+    #[value(name = "powershell")]
+    PowerShell,
+    /// DOS/Windows help, with switches written as `/flag` or `/flag:value`
+    Windows,
+}
+
+impl InputFormat {
+    /// Name of the matching `--input-format` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputFormat::Text => "text",
+            InputFormat::Man => "man",
+            InputFormat::Markdown => "markdown",
+            InputFormat::PowerShell => "powershell",
+            InputFormat::Windows => "windows",
+        }
+    }
+}
+
+/// How much of each description to keep in completion scripts
+#[derive(ValueEnum, Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum DescriptionMode {
+    /// Keep the whole description
+    Full,
+    /// Keep only the first sentence
+    #[default]
+    FirstSentence,
+    /// Drop descriptions entirely
+    None,
+}
+
+/// Directory layout used by `--install-layout`
+#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+pub enum InstallLayout {
+    /// Vendor directories used by distribution packages under a prefix
+    Vendor,
+}
+
+/// Build tool syntax used by `--emit-install-recipe`
+#[derive(ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
+pub enum RecipeFormat {
+    /// Makefile rules honoring PREFIX and DESTDIR
+    Make,
+    /// justfile recipes with a `prefix` variable
+    Just,
+}
+
+#[derive(Parser, Debug, Clone)]
 #[command(
     version,
     author,
@@ -35,7 +160,7 @@ pub struct Cli {
         short = 'c',
         help = "Extract options from a command's help or man page",
         long_help = "Extract CLI options from the help texts or man pages associated with the command. Subcommand pages are also scanned automatically.",
-        conflicts_with_all = ["file", "subcommand", "loadjson"],
+        conflicts_with_all = ["file", "subcommand", "loadjson", "loadjson5"],
     )]
     pub command: Option<String>,
 
@@ -45,7 +170,7 @@ pub struct Cli {
         short = 'f',
         help = "Extract options from a help text file",
         long_help = "Extract CLI options from a text file containing help or manpage output.",
-        conflicts_with_all = ["command", "subcommand", "loadjson"],
+        conflicts_with_all = ["command", "subcommand", "loadjson", "loadjson5"],
     )]
     pub file: Option<String>,
 
@@ -55,7 +180,7 @@ pub struct Cli {
         short = 's',
         help = "Extract options from a subcommand",
         long_help = "Extract CLI options from a subcommand. The format is command-subcommand (for example: git-log).",
-        conflicts_with_all = ["command", "file", "loadjson"],
+        conflicts_with_all = ["command", "file", "loadjson", "loadjson5"],
     )]
     pub subcommand: Option<String>,
 
@@ -65,17 +190,58 @@ pub struct Cli {
         short = 'l',
         help = "Load a Command JSON file",
         long_help = "Load a JSON file that uses d2o's Command schema and operate on that instead of parsing help text.",
-        conflicts_with_all = ["command", "file", "subcommand"],
+        conflicts_with_all = ["command", "file", "subcommand", "loadjson5"],
     )]
     pub loadjson: Option<String>,
 
-    /// Output format: bash, zsh, fish, json, native, elvish, nushell
+    /// Reject unknown fields in Command JSON
+    #[arg(
+        long,
+        requires = "loadjson",
+        help = "Reject unknown fields in --loadjson files",
+        long_help = "Fail when a --loadjson or --merge-json file has a field the Command schema does not define, instead of silently ignoring it, so typos in hand-written JSON are caught."
+    )]
+    pub strict_json: bool,
+
+    /// Further Command JSON files to merge into --loadjson
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "loadjson",
+        help = "Merge another Command JSON file (repeatable)",
+        long_help = "Merge FILE into the command loaded with --loadjson before generating output, for assembling completions from several extraction runs. May be given more than once; files are merged in order, missing fields are filled in, options with the same names keep the most informative description and subcommands with the same name are merged recursively."
+    )]
+    pub merge_json: Vec<String>,
+
+    /// Load JSON5 file in Command schema
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Load a Command JSON5 file",
+        long_help = "Load a JSON5 file that uses d2o's Command schema, such as one written by --format json5 and edited by hand. Comments, unquoted keys, single-quoted strings and trailing commas are accepted.",
+        conflicts_with_all = ["command", "file", "subcommand", "loadjson"],
+    )]
+    pub loadjson5: Option<String>,
+
+    /// Markup of the input: plain text, man page source, Markdown or PowerShell help
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Select input format",
+        long_help = "Select the markup of the input. text (the default) parses help or rendered man page output by its column layout. man parses man page source directly; BSD mdoc pages (.Fl/.Ar/.Op macros) yield exact flags and arguments. markdown parses only the fenced text, console and sh code blocks of a document such as a README, ignoring the prose around them. powershell also reads PowerShell-style -Name:<Type> parameters, taking the type as the argument.",
+        default_value_t = InputFormat::Text,
+    )]
+    pub input_format: InputFormat,
+
+    /// Output format: bash, zsh, fish, powershell, json, json5, native, tsv, markdown, compgen, carapace, elvish, nushell
     #[arg(
         long,
         short = 'o',
         help = "Select output format",
-        long_help = "Select output format: bash, zsh, fish, json, native, elvish, or nushell.",
-        value_parser = ["bash", "zsh", "fish", "json", "native", "elvish", "nushell"],
+        long_help = "Select output format: bash, zsh, fish, powershell, json, json5, native, tsv (alias summary), markdown, compgen, carapace, elvish, or nushell. tsv prints one tab-separated row per option and subcommand; markdown renders a reference page; compgen prints just the space-separated flag list for `compgen -W`; carapace writes a carapace-spec YAML file. The default can be set with the D2O_FORMAT environment variable.",
+        env = "D2O_FORMAT",
+        value_parser = ["bash", "zsh", "fish", "powershell", "json", "json5", "native", "tsv", "summary", "markdown", "compgen", "carapace", "elvish", "nushell"],
         default_value = "native",
     )]
     pub format: String,
@@ -89,6 +255,66 @@ pub struct Cli {
     )]
     pub json: bool,
 
+    /// Emit option names as typed objects in JSON output
+    #[arg(
+        long,
+        help = "Emit typed option names in JSON",
+        long_help = "Serialize each option name in JSON output as an object with its raw text and type (for example, {\"raw\": \"-display\", \"type\": \"OLDTYPE\"}) instead of a plain string, so --loadjson does not have to re-derive the type."
+    )]
+    pub json_typed: bool,
+
+    /// Sort JSON output for stable diffs
+    #[arg(
+        long,
+        help = "Sort options and subcommands in JSON output",
+        long_help = "Sort options by canonical name (the first long name, or the first name) and subcommands by name in --format json output, so the same input always produces byte-identical JSON that is easy to diff and check into version control."
+    )]
+    pub json_sorted: bool,
+
+    /// Keep empty sections in JSON output for a fixed schema
+    #[arg(
+        long,
+        help = "Always emit subcommands and version in JSON output",
+        long_help = "Write the subcommands and version keys in --format json output even when they are empty, as [] and \"\", so every command object has the same keys. By default empty sections are left out."
+    )]
+    pub include_empty_sections: bool,
+
+    /// Render native output as an aligned table
+    #[arg(
+        long,
+        help = "Render native output as an aligned table",
+        long_help = "Render --format native output as an aligned table: option names padded to a common column and descriptions wrapped to the terminal width. Has no effect on other formats."
+    )]
+    pub pretty_native: bool,
+
+    /// Wrap descriptions in text output to this many columns
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Wrap descriptions in native/markdown output",
+        long_help = "Wrap descriptions in native and markdown output so lines stay within N columns. With --pretty-native this replaces the terminal width. Shell completion formats are unaffected; they truncate descriptions instead."
+    )]
+    pub max_line_width: Option<usize>,
+
+    /// Start completion scripts with a "Generated by d2o" comment
+    #[arg(
+        long,
+        help = "Prepend a generated-by header comment",
+        long_help = "Start the output with a comment naming d2o, its version and the command, for example \"# Generated by d2o 0.3.0 for git\". Useful for vendor completion files. Formats without comments (json, native, tsv) are left unchanged."
+    )]
+    pub header: bool,
+
+    /// Check that the command exists before fetching its help
+    #[arg(
+        long,
+        help = "Check that the command exists first",
+        long_help = "Check that the program named by --command or --subcommand can be found on PATH before running it, failing early with a clear \"command not found\" error. Pass --require-command false to skip the check.",
+        default_value = "true",
+        action = clap::ArgAction::Set,
+        value_parser = clap::value_parser!(bool),
+    )]
+    pub require_command: bool,
+
     /// Skip scanning manpage and focus on help text
     #[arg(
         long,
@@ -104,7 +330,7 @@ pub struct Cli {
         short = 'L',
         help = "List discovered subcommands",
         long_help = "List subcommands discovered from the parsed help text instead of generating completions.",
-        conflicts_with = "loadjson"
+        conflicts_with_all = ["loadjson", "loadjson5"]
     )]
     pub list_subcommands: bool,
 
@@ -114,10 +340,57 @@ pub struct Cli {
         short = 'd',
         help = "Run preprocessing only",
         long_help = "Run only the preprocessing phase and print the parsed option/description pairs for debugging.",
-        conflicts_with = "loadjson"
+        conflicts_with_all = ["loadjson", "loadjson5"]
     )]
     pub debug: bool,
 
+    /// Locale to run the help command under
+    #[arg(
+        long,
+        value_name = "LOCALE",
+        help = "Run the command under this locale",
+        long_help = "Set LC_ALL and LANG to LOCALE for the help and man commands d2o runs. Use --lang C to get English section headers (Usage:, Options:) from tools that translate their help."
+    )]
+    pub lang: Option<String>,
+
+    /// Extra arguments placed between the command and --help
+    #[arg(
+        long,
+        value_name = "ARGS",
+        requires = "command",
+        allow_hyphen_values = true,
+        help = "Arguments to pass before --help",
+        long_help = "Insert ARGS between the command and --help when fetching help, so --command docker --command-args compose reads `docker compose --help`. Implies reading --help output instead of the man page."
+    )]
+    pub command_args: Option<String>,
+
+    /// Extra help flag whose output is merged with the default help
+    #[arg(
+        long,
+        value_name = "FLAG",
+        help = "Also read help from an extra help flag",
+        long_help = "Also run the command with FLAG (for example --helpfull or --help=verbose) and merge its output with the default --help output. Useful for gflags/abseil-based tools that hide most flags from the short help. Only applies when reading help text, not man pages."
+    )]
+    pub help_variant: Option<String>,
+
+    /// Use the group header as description for undescribed options
+    #[arg(
+        long,
+        help = "Describe options by their group header",
+        long_help = "Give options that have no description of their own the header of the group they are listed under (for example, an undescribed --proxy under \"Network:\" gets the description \"Network\"). Generic headers such as \"Options:\" are not used. Without this, undescribed options are dropped."
+    )]
+    pub group_as_desc_fallback: bool,
+
+    /// Number of spaces a tab expands to before parsing
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Set tab width used when expanding tabs",
+        long_help = "Expand tabs in the input to N spaces before parsing. Tab-aligned help (common in Go tools) keeps its option/description columns as long as N is at least 2.",
+        default_value_t = DEFAULT_TAB_WIDTH,
+    )]
+    pub tab_width: usize,
+
     /// Set upper bound of the depth of subcommand level
     #[arg(
         long,
@@ -128,15 +401,42 @@ pub struct Cli {
     )]
     pub depth: usize,
 
-    /// Generate shell completions
+    /// Add `<command>-<name>` executables on PATH as subcommands
     #[arg(
         long,
+        help = "Discover git-style subcommands on PATH",
+        long_help = "Add every executable on PATH named <command>-<name> (for example git-lfs or cargo-watch) as a subcommand of --command, the way git and cargo dispatch external subcommands."
+    )]
+    pub path_subcommands: bool,
+
+    /// Leave shell builtins and the command itself out of PATH subcommands
+    #[arg(
+        long,
+        help = "Exclude builtins from PATH subcommands",
+        long_help = "Drop PATH-discovered subcommands whose name is a shell builtin (such as help, cd or exit) or the base command itself.",
+        requires = "path_subcommands"
+    )]
+    pub exclude_builtin: bool,
+
+    /// Generate shell completions for d2o itself, or for the parsed command
+    #[arg(
+        long = "self-completions",
+        visible_alias = "completions",
         short = 'C',
         value_name = "SHELL",
-        help = "Generate shell completion script",
-        long_help = "Generate a shell completion script for the given shell (bash, zsh, fish, powershell, elvish, nushell)."
+        help = "Generate shell completion script for d2o",
+        long_help = "Generate d2o's own completion script for the given shell (bash, zsh, fish, powershell, elvish, nushell), or with all, for every shell in turn, each section headed by a \"# ===== <shell> =====\" comment. When combined with an input source such as --command or --file, this instead generates the parsed command's completion, as a shortcut for --format <SHELL>; all cannot be used that way."
     )]
-    pub completions: Option<Shell>,
+    pub self_completions: Option<CompletionsTarget>,
+
+    /// Print how to install the generated completion for a shell
+    #[arg(
+        long,
+        value_name = "SHELL",
+        help = "Print install steps for a shell's completion",
+        long_help = "After generating, print to stderr where to save the completion file for the given shell and how to load it (for zsh, the fpath and compinit setup). Paths follow XDG_CONFIG_HOME and XDG_DATA_HOME when set. The script itself still goes to stdout."
+    )]
+    pub print_install_instructions: Option<Shell>,
 
     /// Write completion script to RC file (~/.bashrc, ~/.zshrc, etc.)
     /// Automatically detects shell and appends to appropriate rc file
@@ -148,6 +448,66 @@ pub struct Cli {
     )]
     pub write: bool,
 
+    /// Syntax-check the generated script with its shell
+    #[arg(
+        long,
+        help = "Syntax-check the generated script",
+        long_help = "After generating, check the script with the target shell's no-exec mode (bash -n, zsh -n, fish -n or elvish -compileonly) and report the result on stderr. Exits with an error if the check fails. Skipped silently when the shell is not installed or the format has no checker."
+    )]
+    pub verify: bool,
+
+    /// Print how long each phase took
+    #[arg(
+        long,
+        help = "Print phase timings to stderr",
+        long_help = "After the run, print a table of wall-clock times for reading the input, normalizing it, parsing it and generating the output, plus the total, to stderr. Printed regardless of the log level, for reporting slow commands."
+    )]
+    pub profile: bool,
+
+    /// Whether --write may replace an existing file
+    #[arg(
+        long,
+        help = "Allow --write to replace existing files",
+        long_help = "Control whether --write may replace an existing completion file. With --overwrite false, d2o refuses to write and reports the path of the existing file, leaving it untouched.",
+        default_value = "true",
+        action = clap::ArgAction::Set,
+        value_parser = clap::value_parser!(bool),
+    )]
+    pub overwrite: bool,
+
+    /// Write bash, zsh and fish completions into a packaging layout
+    #[arg(
+        long,
+        value_enum,
+        value_name = "LAYOUT",
+        help = "Write completions into an install layout",
+        long_help = "Write bash, zsh and fish completions as files under --prefix instead of printing them, for distribution packaging. vendor uses share/bash-completion/completions/<name>, share/zsh/site-functions/_<name> and share/fish/vendor_completions.d/<name>.fish.",
+        requires = "prefix",
+        conflicts_with = "write"
+    )]
+    pub install_layout: Option<InstallLayout>,
+
+    /// Installation prefix for --install-layout
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Installation prefix for --install-layout",
+        long_help = "Installation prefix that --install-layout writes under, such as /usr or a package staging directory like $DESTDIR/usr.",
+        requires = "install_layout"
+    )]
+    pub prefix: Option<String>,
+
+    /// Print a build recipe that installs the completions
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TOOL",
+        help = "Print a make or just recipe installing the completions",
+        long_help = "Instead of the completion script, print a make or just recipe with one target per shell (bash, zsh and fish) that runs d2o with the same input and shell output flags as this run and writes the output into the vendor directories used by --install-layout vendor, plus an install-completions target that runs them all. The make recipe honors PREFIX and DESTDIR; the just recipe takes a prefix variable.",
+        conflicts_with_all = ["write", "install_layout"]
+    )]
+    pub emit_install_recipe: Option<RecipeFormat>,
+
     /// Use bash-completion extended format for bash output
     /// (encodes descriptions as name:Description and calls __ltrim_colon_completions if available)
     #[arg(
@@ -158,6 +518,131 @@ pub struct Cli {
     )]
     pub bash_completion_compat: bool,
 
+    /// Complete stacked boolean short flags in bash output
+    #[arg(
+        long,
+        help = "Complete bundled short flags in bash",
+        long_help = "Let bash output complete stacked short flags (for example -x, then -xv, -xf) when every short flag of the command is boolean. Zsh output always allows stacking through _arguments -s."
+    )]
+    pub bundle_shorts: bool,
+
+    /// Whether bash completion keeps the cursor after a completed word
+    #[arg(
+        long,
+        help = "Emit -o nospace in bash completions",
+        long_help = "Control whether the bash complete line uses -o nospace. With the default true, bash adds no space after a completed word; with --bash-nospace false it adds one, which suits flags that take no attached value.",
+        default_value = "true",
+        action = clap::ArgAction::Set,
+        value_parser = clap::value_parser!(bool),
+    )]
+    pub bash_nospace: bool,
+
+    /// Prefix for the bash completion function name
+    #[arg(
+        long,
+        value_name = "STR",
+        help = "Prefix the bash completion function name",
+        long_help = "Name the bash completion function STR<command> instead of _<command> (for example, --bash-function-prefix __d2o_ gives __d2o_mytool), to avoid clashes when many generated completions are sourced together."
+    )]
+    pub bash_function_prefix: Option<String>,
+
+    /// Additional command words that get the same completion
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Also register the completion for NAME (repeatable)",
+        long_help = "Register the generated completion under NAME as well as the parsed command, for wrapper scripts and aliases such as a `mt` that runs `mytool`. bash adds another complete -F line, zsh lists NAME on the #compdef line and fish adds complete -c NAME -w <command>. May be given more than once."
+    )]
+    pub also_complete: Vec<String>,
+
+    /// Emit elvish completions as a module
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Wrap elvish output in a module function",
+        long_help = "Make --format elvish output an importable module that defines fn NAME-completions instead of setting edit:completion:arg-completer directly. Save it as NAME.elv in your module path and register it with `use NAME; set edit:completion:arg-completer[<command>] = $NAME:NAME-completions~`."
+    )]
+    pub elvish_module: Option<String>,
+
+    /// Name for the zsh `#compdef` line
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Override the zsh #compdef name",
+        long_help = "Write NAME on the #compdef line of zsh output instead of the command name, for tools whose completion is registered under a different name. The completion function keeps the command's name (_<command>)."
+    )]
+    pub zsh_compdef_name: Option<String>,
+
+    /// Maximum description length in completion scripts
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Limit description length in completions",
+        long_help = "Limit option descriptions in generated completion scripts to N characters. By default each shell uses its own limit (for example, 80 for fish and 120 for zsh)."
+    )]
+    pub max_desc_len: Option<usize>,
+
+    /// How much of each description to keep in completion scripts
+    #[arg(
+        long = "completion-description-mode",
+        value_enum,
+        value_name = "MODE",
+        help = "Control descriptions in completion scripts",
+        long_help = "Control how much of each description is kept in completion scripts: full keeps the whole text (only --max-desc-len still applies), first-sentence keeps the first sentence within the shell's length limit, and none drops descriptions for smaller output.",
+        default_value_t = DescriptionMode::FirstSentence,
+    )]
+    pub description_mode: DescriptionMode,
+
+    /// Patterns to mask in descriptions
+    #[arg(
+        long,
+        value_name = "REGEX",
+        help = "Mask text matching REGEX in descriptions (repeatable)",
+        long_help = "Replace every match of REGEX in command, option and positional descriptions with ***, before the result is cached or printed, so tokens or private paths embedded in help text are never written to disk. May be given more than once."
+    )]
+    pub redact: Vec<String>,
+
+    /// Merge all subcommand options into the top-level command
+    #[arg(
+        long,
+        help = "Flatten subcommand options into one option list",
+        long_help = "Drop subcommands and complete the union of the options of the command and every subcommand at the top level, with options that share a name merged into one. For minimal completion targets that cannot tell subcommands apart."
+    )]
+    pub flatten_options: bool,
+
+    /// Fold misread old-style names into their long options
+    #[arg(
+        long,
+        help = "Merge -name options into matching --name options",
+        long_help = "Treat an old-style single-dash name such as -verbose as a misreading of the long option --verbose when the command also has that long option, and merge the two, keeping the more informative description. Old-style names without a long counterpart are left alone."
+    )]
+    pub reclassify: bool,
+
+    /// Transliterate non-ASCII characters in descriptions to ASCII
+    #[arg(
+        long,
+        help = "Restrict descriptions to ASCII",
+        long_help = "Transliterate non-ASCII characters in option and subcommand descriptions to their closest ASCII equivalents before generating output (for example, café becomes cafe). Useful for terminals or completion systems that cannot handle Unicode."
+    )]
+    pub ascii_only: bool,
+
+    /// Keep the help text's option order in completion menus
+    #[arg(
+        long,
+        help = "Keep the declared option order in completion menus",
+        long_help = "Show options in the order the help text lists them instead of letting the shell sort them. For fish, every complete line gets -k and the lines are written in reverse, since fish shows later -k completions first. Only fish output changes: bash and zsh sort completion menus themselves, and the json, native and markdown formats always list options in the parsed order.",
+        conflicts_with = "normalize"
+    )]
+    pub preserve_order: bool,
+
+    /// Normalize the whole command tree before output
+    #[arg(
+        long,
+        help = "Merge, dedup and sort the whole command tree",
+        long_help = "Normalize the whole command tree before generating output: subcommands that appear more than once are merged, text is trimmed, duplicate options and option names are removed, and options and subcommands are sorted. Useful with --loadjson trees assembled from several sources."
+    )]
+    pub normalize: bool,
+
     /// Enable caching of parsed commands (default: enabled)
     #[arg(
         long,
@@ -173,12 +658,41 @@ pub struct Cli {
     #[arg(
         long,
         help = "Set cache TTL in hours",
-        long_help = "Set the time-to-live for cache entries in hours. Entries older than this are considered stale and will be re-parsed.",
+        long_help = "Set the time-to-live for cache entries in hours. Entries older than this are considered stale and will be re-parsed. The default can be set with the D2O_CACHE_TTL environment variable.",
+        env = "D2O_CACHE_TTL",
         default_value_t = DEFAULT_CACHE_TTL_HOURS,
         value_name = "HOURS",
     )]
     pub cache_ttl: u64,
 
+    /// Refresh an entry's TTL whenever it is used
+    #[arg(
+        long,
+        help = "Refresh cache TTL on cache hits",
+        long_help = "Reset the time-to-live of a cache entry each time it is used, so frequently used commands never expire while their help text stays unchanged."
+    )]
+    pub cache_refresh_on_hit: bool,
+
+    /// Parse inputs into the cache without producing output
+    #[arg(
+        long,
+        help = "Populate the cache without output",
+        long_help = "Parse each input and store the resulting Command in the cache, then print how many entries were written instead of generating completions. Caching is used even if --cache false is given. Combine with --commands-file to warm the cache for many commands, for example in CI.",
+        conflicts_with_all = ["loadjson", "loadjson5"]
+    )]
+    pub seed_cache: bool,
+
+    /// File listing commands to seed into the cache, one per line
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Read commands to seed from a file",
+        long_help = "Read command names from FILE, one per line, and seed each into the cache. Blank lines and lines starting with # are ignored.",
+        requires = "seed_cache",
+        conflicts_with_all = ["command", "file", "subcommand", "loadjson", "loadjson5"],
+    )]
+    pub commands_file: Option<String>,
+
     /// Clear all cached entries
     #[arg(
         long,
@@ -195,6 +709,14 @@ pub struct Cli {
     )]
     pub cache_stats: bool,
 
+    /// List cached entries
+    #[arg(
+        long,
+        help = "List cache entries",
+        long_help = "Print a table of cached entries with their key, command name, age, size and whether they are still within the TTL."
+    )]
+    pub cache_list: bool,
+
     /// Set the level of verbosity (-v, -vv, -q, etc.)
     #[command(flatten)]
     pub verbosity: Verbosity,
@@ -203,13 +725,27 @@ pub struct Cli {
 impl Cli {
     /// Get the effective format, considering --json flag as legacy
     pub fn effective_format(&self) -> &str {
-        if self.json { "json" } else { &self.format }
+        if self.json {
+            "json"
+        } else if let Some(shell) = self.self_completions.and_then(|t| t.shell())
+            && self.has_input_source()
+        {
+            shell.as_str()
+        } else {
+            &self.format
+        }
+    }
+
+    /// Check if any input source (command, file, subcommand or JSON) was given
+    pub fn has_input_source(&self) -> bool {
+        self.get_input().is_some() || self.subcommand.is_some() || self.commands_file.is_some()
     }
 
     /// Get the input file/command, prioritizing loadjson
     pub fn get_input(&self) -> Option<&str> {
         self.loadjson
             .as_deref()
+            .or(self.loadjson5.as_deref())
             .or(self.file.as_deref())
             .or(self.command.as_deref())
     }
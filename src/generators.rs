@@ -1,9 +1,13 @@
-use crate::types::{Command, Opt, OptName, OptNameType};
+use crate::cli::DescriptionMode;
+use crate::json_gen::{Json5Generator, JsonGenerator};
+use crate::types::{Command, Opt, OptName, OptNameType, Positional};
 use aho_corasick::AhoCorasick;
-use ecow::EcoString;
+use ecow::{EcoString, eco_format};
 use memchr::memchr;
+use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::fmt::Write;
+use std::str::FromStr;
 use std::sync::LazyLock;
 
 // Pre-compiled Aho-Corasick automaton for file/dir/path matching (SIMD-accelerated)
@@ -14,14 +18,337 @@ static FILE_PATH_MATCHER: LazyLock<AhoCorasick> = LazyLock::new(|| {
         .unwrap()
 });
 
+/// Output settings shared by all generators.
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Use bash-completion's extended `name:Description` format in bash output
+    pub bash_completion_compat: bool,
+    /// Serialize option names in JSON as `{raw, type}` objects instead of plain strings
+    pub json_typed: bool,
+    /// Sort options by canonical name and subcommands by name in JSON
+    pub json_sorted: bool,
+    /// Always emit `subcommands` and `version` in JSON, even when empty
+    pub json_include_empty_sections: bool,
+    /// Override every generator's default description length limit
+    pub max_desc_len: Option<usize>,
+    /// How much of each description to keep
+    pub description_mode: DescriptionMode,
+    /// Complete stacked short flags (`-xvf`) in bash when every short flag is boolean
+    pub bundle_shorts: bool,
+    /// Put `-o nospace` on bash's `complete` line (the default); without it
+    /// accepted flags get a trailing space
+    pub bash_nospace: bool,
+    /// Prefix of the bash completion function in place of `_`
+    pub bash_function_prefix: Option<EcoString>,
+    /// Extra command words to register the completion under, such as a
+    /// wrapper script or alias (bash, zsh and fish)
+    pub also_complete: Vec<EcoString>,
+    /// Wrap elvish output as an importable module defining
+    /// `fn <name>-completions` instead of setting the arg-completer directly
+    pub elvish_module: Option<EcoString>,
+    /// Name for zsh's `#compdef` line in place of the command name
+    pub zsh_compdef_name: Option<EcoString>,
+    /// Keep the declared option order in completion menus instead of
+    /// letting the shell sort them (fish `complete -k`)
+    pub preserve_order: bool,
+    /// Wrap native and markdown output to this many columns
+    pub max_line_width: Option<usize>,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            bash_completion_compat: false,
+            json_typed: false,
+            json_sorted: false,
+            json_include_empty_sections: false,
+            max_desc_len: None,
+            description_mode: DescriptionMode::default(),
+            bundle_shorts: false,
+            bash_nospace: true,
+            bash_function_prefix: None,
+            also_complete: Vec::new(),
+            elvish_module: None,
+            zsh_compdef_name: None,
+            preserve_order: false,
+            max_line_width: None,
+        }
+    }
+}
+
+impl GeneratorOptions {
+    /// Shorten a description for a completion menu according to
+    /// `description_mode`. The first sentence is capped at `default_limit`
+    /// characters; `max_desc_len` overrides the cap in every mode.
+    pub fn menu_description<'a>(&self, desc: &'a str, default_limit: usize) -> &'a str {
+        let desc = match self.description_mode {
+            DescriptionMode::Full => match self.max_desc_len {
+                Some(max) => truncate_chars(desc, max),
+                None => desc,
+            },
+            DescriptionMode::FirstSentence => truncate_chars(
+                first_sentence(desc),
+                self.max_desc_len.unwrap_or(default_limit),
+            ),
+            DescriptionMode::None => return "",
+        };
+        desc.trim_end()
+    }
+}
+
+/// Output formats [`generate`] can produce, named as for `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    Nushell,
+    PowerShell,
+    Json,
+    Json5,
+    Carapace,
+    Compgen,
+    Markdown,
+    /// Tab-separated summary, also accepted as `summary`
+    Tsv,
+    Native,
+}
+
+impl OutputFormat {
+    /// Name of the matching `--format` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Bash => "bash",
+            OutputFormat::Zsh => "zsh",
+            OutputFormat::Fish => "fish",
+            OutputFormat::Elvish => "elvish",
+            OutputFormat::Nushell => "nushell",
+            OutputFormat::PowerShell => "powershell",
+            OutputFormat::Json => "json",
+            OutputFormat::Json5 => "json5",
+            OutputFormat::Carapace => "carapace",
+            OutputFormat::Compgen => "compgen",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Native => "native",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "bash" => OutputFormat::Bash,
+            "zsh" => OutputFormat::Zsh,
+            "fish" => OutputFormat::Fish,
+            "elvish" => OutputFormat::Elvish,
+            "nushell" => OutputFormat::Nushell,
+            "powershell" => OutputFormat::PowerShell,
+            "json" => OutputFormat::Json,
+            "json5" => OutputFormat::Json5,
+            "carapace" => OutputFormat::Carapace,
+            "compgen" => OutputFormat::Compgen,
+            "markdown" => OutputFormat::Markdown,
+            "tsv" | "summary" => OutputFormat::Tsv,
+            "native" => OutputFormat::Native,
+            _ => anyhow::bail!("Unknown output option: {}", s),
+        })
+    }
+}
+
+/// Render `cmd` in `format` with default options.
+pub fn generate(format: OutputFormat, cmd: &Command) -> EcoString {
+    generate_with_options(format, cmd, &GeneratorOptions::default())
+}
+
+/// Render `cmd` in `format`, dispatching to the matching generator.
+pub fn generate_with_options(
+    format: OutputFormat,
+    cmd: &Command,
+    options: &GeneratorOptions,
+) -> EcoString {
+    match format {
+        OutputFormat::Bash => BashGenerator::generate_with_options(cmd, options),
+        OutputFormat::Zsh => ZshGenerator::generate_with_options(cmd, options),
+        OutputFormat::Fish => FishGenerator::generate_with_options(cmd, options),
+        OutputFormat::Elvish => ElvishGenerator::generate_with_options(cmd, options),
+        OutputFormat::Nushell => NushellGenerator::generate_with_options(cmd, options),
+        OutputFormat::PowerShell => PowerShellGenerator::generate_with_options(cmd, options),
+        OutputFormat::Json => JsonGenerator::generate_with_options(cmd, options),
+        OutputFormat::Json5 => Json5Generator::generate_with_options(cmd, options),
+        OutputFormat::Carapace => CarapaceGenerator::generate_with_options(cmd, options),
+        OutputFormat::Compgen => BashGenerator::generate_compgen(cmd),
+        OutputFormat::Markdown => {
+            MarkdownGenerator::generate_with_width(cmd, options.max_line_width)
+        }
+        OutputFormat::Tsv => TsvGenerator::generate(cmd),
+        OutputFormat::Native => NativeGenerator::generate_with_width(cmd, options.max_line_width),
+    }
+}
+
+/// Abbreviations whose trailing period does not end a sentence
+const SENTENCE_ABBREVIATIONS: &[&str] = &["e.g", "i.e", "etc", "vs", "cf", "approx"];
+
+/// Cut a description at the end of its first sentence. Unlike
+/// `FishGenerator::truncate_after_period`, periods inside words (`file.txt`,
+/// `1.5`) and after common abbreviations (`e.g.`) do not end the sentence.
+pub fn first_sentence(desc: &str) -> &str {
+    let bytes = desc.as_bytes();
+    for pos in memchr::memchr_iter(b'.', bytes) {
+        let at_word_end = bytes.get(pos + 1).is_none_or(|b| b.is_ascii_whitespace());
+        if !at_word_end {
+            continue;
+        }
+
+        let sentence = &desc[..pos];
+        let last_word = sentence
+            .rsplit(|c: char| c.is_whitespace() || c == '(')
+            .next()
+            .unwrap_or_default();
+        if !SENTENCE_ABBREVIATIONS
+            .iter()
+            .any(|abbr| last_word.eq_ignore_ascii_case(abbr))
+        {
+            // Drop the rest of an ellipsis
+            return sentence.trim_end_matches('.');
+        }
+    }
+    desc
+}
+
+/// Values of a positional written as `{start,stop}` or `<start|stop>`.
+fn positional_choices(name: &str) -> Option<Vec<&str>> {
+    let inner = name.trim_matches(['<', '>', '[', ']', '{', '}', '(', ')', '.']);
+    let choices: Vec<&str> = inner
+        .split([',', '|'])
+        .map(str::trim)
+        .filter(|choice| !choice.is_empty())
+        .collect();
+    let plain = choices.iter().all(|choice| {
+        choice
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    });
+    (choices.len() > 1 && plain).then_some(choices)
+}
+
+/// Fish keywords that break a script when used unquoted in command position
+const FISH_RESERVED_WORDS: &[&str] = &[
+    "and", "begin", "break", "builtin", "case", "command", "continue", "else", "end", "exec",
+    "for", "function", "if", "not", "or", "return", "switch", "time", "while",
+];
+
+/// Check whether a name can appear unquoted in any supported shell.
+#[inline]
+fn is_plain_word(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b'+'))
+}
+
+/// Single-quote a command name for fish when it is a reserved word or
+/// contains characters outside the safe set.
+pub fn fish_quote_name(name: &str) -> Cow<'_, str> {
+    if is_plain_word(name) && !FISH_RESERVED_WORDS.contains(&name) {
+        return Cow::Borrowed(name);
+    }
+
+    Cow::Owned(format!(
+        "'{}'",
+        name.replace('\\', "\\\\").replace('\'', "\\'")
+    ))
+}
+
+/// Single-quote a command name for bash when it contains special characters.
+pub fn bash_quote_name(name: &str) -> Cow<'_, str> {
+    if is_plain_word(name) {
+        return Cow::Borrowed(name);
+    }
+
+    Cow::Owned(format!("'{}'", name.replace('\'', "'\\''")))
+}
+
+/// Escape text for a single-quoted PowerShell string by doubling quotes.
+pub fn powershell_quote(text: &str) -> Cow<'_, str> {
+    if text.contains('\'') {
+        Cow::Owned(text.replace('\'', "''"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Slice `s` to the byte offset `pos`, moving back to the nearest char
+/// boundary so a multi-byte sequence is never split.
+#[inline]
+pub fn slice_to_char_boundary(s: &str, pos: usize) -> &str {
+    if pos >= s.len() {
+        return s;
+    }
+    let mut end = pos;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Slice `s` to at most `max` characters without splitting a UTF-8 sequence.
+#[inline]
+pub fn truncate_chars(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((pos, _)) => &s[..pos],
+        None => s,
+    }
+}
+
+/// Greedily wrap words so each line stays within `width` characters.
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let len = line.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
 pub struct FishGenerator;
 
 impl FishGenerator {
+    /// Fish prints descriptions inline, so keep them short
+    pub const MAX_DESC_LEN: usize = 80;
+
     pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
         // Pre-calculate capacity based on options count
         let estimated_size = 64 + cmd.options.len() * 80;
         let mut buf = String::with_capacity(estimated_size);
-        Self::generate_rec(&mut buf, &[], cmd);
+        Self::generate_rec(&mut buf, None, cmd, options);
+        for name in &options.also_complete {
+            let _ = writeln!(
+                buf,
+                "complete -c {} -w {}",
+                fish_quote_name(name),
+                fish_quote_name(&cmd.name)
+            );
+        }
         // Remove trailing newline if present
         if buf.ends_with('\n') {
             buf.pop();
@@ -29,22 +356,121 @@ impl FishGenerator {
         EcoString::from(buf)
     }
 
-    fn generate_rec(buf: &mut String, path: &[&str], cmd: &Command) {
-        let mut current_path = path.to_vec();
-        current_path.push(&cmd.name);
-        let path_str = current_path.join("_");
+    fn generate_rec(
+        buf: &mut String,
+        parent: Option<&str>,
+        cmd: &Command,
+        options: &GeneratorOptions,
+    ) {
+        // The root is the real command; subcommands use their qualified name
+        let qualified = match parent {
+            Some(parent) => Command::qualified_name(&[parent, cmd.name.as_str()]),
+            None => cmd.name.clone(),
+        };
+        let path_str = fish_quote_name(&qualified);
+        let passthrough = cmd.has_passthrough();
+        let longs: BTreeSet<&str> = cmd
+            .options
+            .iter()
+            .flat_map(|opt| opt.names.iter())
+            .filter(|name| name.opt_type == OptNameType::LongType)
+            .map(|name| name.raw.trim_start_matches('-'))
+            .collect();
 
-        for opt in cmd.options.iter() {
-            for name in opt.names.iter() {
-                if !Self::should_skip_option(name) {
-                    Self::write_option_line(buf, &path_str, name, opt);
+        // fish lists later `-k` completions first, so write them backwards,
+        // positionals before the options they follow in the menu
+        if options.preserve_order {
+            for positional in cmd.positionals.iter().rev() {
+                Self::write_positional_line(buf, &path_str, positional, options);
+            }
+        }
+        let opts: Vec<&Opt> = if options.preserve_order {
+            cmd.options.iter().rev().collect()
+        } else {
+            cmd.options.iter().collect()
+        };
+        for opt in opts {
+            let names: Vec<&OptName> = if options.preserve_order {
+                opt.names.iter().rev().collect()
+            } else {
+                opt.names.iter().collect()
+            };
+            for name in names {
+                if Self::should_skip_option(name) {
+                    continue;
+                }
+
+                let mut conditions = Vec::new();
+                // Stop offering flags once a passthrough `--` is on the line
+                if passthrough {
+                    conditions.push(String::from("not contains -- -- (commandline -opc)"));
+                }
+                // `--flag` and `--no-flag` exclude each other
+                if let Some(negation) = Self::negation_of(name, &longs) {
+                    conditions.push(format!("not __fish_seen_argument -l {}", negation));
                 }
+                let condition = if conditions.is_empty() {
+                    String::new()
+                } else {
+                    format!(" -n '{}'", conditions.join("; and "))
+                };
+
+                Self::write_option_line(buf, &path_str, &condition, name, opt, options);
+            }
+        }
+
+        if !options.preserve_order {
+            for positional in cmd.positionals.iter() {
+                Self::write_positional_line(buf, &path_str, positional, options);
             }
         }
 
         for subcmd in cmd.subcommands.iter() {
-            Self::generate_rec(buf, &current_path, subcmd);
+            Self::generate_rec(buf, Some(&qualified), subcmd, options);
+        }
+    }
+
+    /// Complete a positional before any subcommand word: files for a
+    /// file-like positional, the listed values for `{a,b}` or `a|b`
+    /// choices. Other placeholders have nothing to offer.
+    fn write_positional_line(
+        buf: &mut String,
+        path_str: &str,
+        positional: &Positional,
+        options: &GeneratorOptions,
+    ) {
+        let action = match positional_choices(&positional.name) {
+            Some(choices) => format!("-f -a '{}'", choices.join(" ")),
+            None if FILE_PATH_MATCHER.is_match(positional.name.as_str()) => String::from("-F"),
+            None => return,
+        };
+        let desc = options.menu_description(&positional.description, Self::MAX_DESC_LEN);
+
+        let _ = write!(
+            buf,
+            "complete -c {} -n '__fish_use_subcommand'{} {}",
+            path_str,
+            Self::keep_order_flag(options),
+            action
+        );
+        if !desc.is_empty() {
+            let _ = write!(buf, " -d '{}'", desc.replace('\'', "\\'"));
+        }
+        let _ = writeln!(buf);
+    }
+
+    /// The dashless counterpart of a negatable long flag (`no-color` for
+    /// `--color` and the reverse), if the command has both.
+    fn negation_of<'a>(name: &'a OptName, longs: &BTreeSet<&'a str>) -> Option<String> {
+        if name.opt_type != OptNameType::LongType {
+            return None;
         }
+        let dashless = name.raw.trim_start_matches('-');
+        let counterpart = match dashless.strip_prefix("no-") {
+            Some(positive) => positive.to_string(),
+            None => format!("no-{}", dashless),
+        };
+        longs.contains(counterpart.as_str()).then_some(counterpart)
     }
 
     #[inline]
@@ -55,16 +481,35 @@ impl FishGenerator {
         )
     }
 
-    fn write_option_line(buf: &mut String, path_str: &str, name: &OptName, opt: &Opt) {
+    fn write_option_line(
+        buf: &mut String,
+        path_str: &str,
+        condition: &str,
+        name: &OptName,
+        opt: &Opt,
+        options: &GeneratorOptions,
+    ) {
         let dashless = name.raw.trim_start_matches('-');
         let flag = Self::opt_type_to_flag(name.opt_type);
         let arg_flag = Self::opt_arg_to_flag(opt);
-        let desc = Self::truncate_after_period(&opt.description);
+        let desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
+        let keep = Self::keep_order_flag(options);
+
+        if desc.is_empty() {
+            let _ = writeln!(
+                buf,
+                "complete -c {}{}{} {} '{}' {}",
+                path_str, condition, keep, flag, dashless, arg_flag
+            );
+            return;
+        }
 
         let _ = writeln!(
             buf,
-            "complete -c {} {} '{}' {} -d '{}'",
+            "complete -c {}{}{} {} '{}' {} -d '{}'",
             path_str,
+            condition,
+            keep,
             flag,
             dashless,
             arg_flag,
@@ -72,12 +517,20 @@ impl FishGenerator {
         );
     }
 
+    #[inline]
+    fn keep_order_flag(options: &GeneratorOptions) -> &'static str {
+        if options.preserve_order { " -k" } else { "" }
+    }
+
     #[inline]
     fn opt_type_to_flag(opt_type: OptNameType) -> &'static str {
         match opt_type {
             OptNameType::LongType => "-l",
             OptNameType::ShortType => "-s",
             OptNameType::OldType => "-o",
+            // fish has no slash-flag or plus-flag switch, so offer them as
+            // plain arguments
+            OptNameType::SlashType | OptNameType::PlusType => "-a",
             _ => "",
         }
     }
@@ -106,7 +559,7 @@ impl FishGenerator {
     pub fn truncate_after_period(line: &str) -> &str {
         // Use memchr for SIMD-accelerated '.' search
         match memchr(b'.', line.as_bytes()) {
-            Some(pos) => &line[..pos],
+            Some(pos) => slice_to_char_boundary(line, pos),
             None => line,
         }
     }
@@ -115,64 +568,228 @@ impl FishGenerator {
 pub struct ZshGenerator;
 
 impl ZshGenerator {
+    /// Zsh shows descriptions in a menu, so there is room for more text
+    pub const MAX_DESC_LEN: usize = 120;
+
     pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
         let estimated_size = 256 + cmd.options.len() * 64;
         let mut buf = String::with_capacity(estimated_size);
 
-        let _ = writeln!(buf, "#compdef {}", cmd.name);
+        let function_name = Command::qualified_name(&[cmd.name.as_str()]);
+
+        let compdef_name = options.zsh_compdef_name.as_deref().unwrap_or(&cmd.name);
+        let _ = write!(buf, "#compdef {}", compdef_name);
+        for name in &options.also_complete {
+            let _ = write!(buf, " {}", name);
+        }
         let _ = writeln!(buf);
-        let _ = writeln!(buf, "_{}() {{", cmd.name);
+        let _ = writeln!(buf);
+        let _ = writeln!(buf, "_{}() {{", function_name);
         let _ = writeln!(buf, "  local -a options");
         let _ = writeln!(buf);
 
         for opt in cmd.options.iter() {
-            Self::write_opt(&mut buf, opt);
+            Self::write_opt(&mut buf, opt, options);
+        }
+        for (i, positional) in cmd.positionals.iter().enumerate() {
+            Self::write_positional(&mut buf, i + 1, positional, options);
         }
 
         let _ = writeln!(buf, "  _arguments -s -S $options");
         let _ = writeln!(buf, "}}");
         let _ = writeln!(buf);
-        let _ = write!(buf, "_{} \"$@\"", cmd.name);
+        let _ = write!(buf, "_{} \"$@\"", function_name);
 
         EcoString::from(buf)
     }
 
-    fn write_opt(buf: &mut String, opt: &Opt) {
-        let desc = FishGenerator::truncate_after_period(&opt.description);
+    fn write_opt(buf: &mut String, opt: &Opt, options: &GeneratorOptions) {
+        let desc =
+            Self::escape_spec(options.menu_description(&opt.description, Self::MAX_DESC_LEN));
+        let argument = Self::escape_spec(&opt.argument);
 
         for name in opt.names.iter() {
             if matches!(
                 name.opt_type,
-                OptNameType::SingleDashAlone | OptNameType::DoubleDashAlone
+                OptNameType::SingleDashAlone
+                    | OptNameType::DoubleDashAlone
+                    | OptNameType::SlashType
             ) {
                 continue;
             }
 
-            if opt.argument.is_empty() {
+            if opt.optional_argument && !opt.argument.is_empty() {
+                // Old-style `-name` options take `-name=value` like long ones
+                let attach = if name.opt_type == OptNameType::ShortType {
+                    "-"
+                } else {
+                    "=-"
+                };
+                let desc = if desc.is_empty() {
+                    String::new()
+                } else {
+                    format!("[{}]", desc)
+                };
+                // `=-`/`-` keep an optional value in the same word, `::` marks
+                // it optional
+                let _ = writeln!(
+                    buf,
+                    "  options+=('{}{}{}::{}:')",
+                    name.raw, attach, desc, argument
+                );
+            } else if opt.argument.is_empty() && desc.is_empty() {
+                let _ = writeln!(buf, "  options+=('{}')", name.raw);
+            } else if opt.argument.is_empty() {
                 let _ = writeln!(buf, "  options+=('{}[{}]')", name.raw, desc);
+            } else if desc.is_empty() {
+                let _ = writeln!(
+                    buf,
+                    "  options+=('{}[{}]:{}:{}')",
+                    name.raw,
+                    argument,
+                    argument,
+                    Self::argument_action(opt)
+                );
             } else {
                 let _ = writeln!(
                     buf,
-                    "  options+=('{}[{} {}]')",
-                    name.raw, opt.argument, desc
+                    "  options+=('{}[{} {}]:{}:{}')",
+                    name.raw,
+                    argument,
+                    desc,
+                    argument,
+                    Self::argument_action(opt)
                 );
             }
         }
     }
+
+    /// Escape text for an `_arguments` spec: `[`, `]` and `:` delimit its
+    /// parts, and `'` would end the single-quoted word.
+    fn escape_spec(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '[' | ']' | ':' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '\'' => escaped.push_str("'\\''"),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// `N:message:action` for the `n`th positional: `*` instead of `N` when
+    /// it repeats (`<FILE>...`), `::` when it is optional (`[FILE]`).
+    fn write_positional(
+        buf: &mut String,
+        n: usize,
+        positional: &Positional,
+        options: &GeneratorOptions,
+    ) {
+        let name = positional.name.as_str();
+        let position = if name.ends_with("...") {
+            String::from("*")
+        } else {
+            n.to_string()
+        };
+        let optional = if name.starts_with('[') { "::" } else { ":" };
+        let desc = options.menu_description(&positional.description, Self::MAX_DESC_LEN);
+        let message = if desc.is_empty() {
+            name.trim_matches(['<', '>', '[', ']', '.'])
+        } else {
+            desc
+        };
+        let action = match positional_choices(name) {
+            Some(choices) => Cow::Owned(format!("({})", choices.join(" "))),
+            None if FILE_PATH_MATCHER.is_match(name) => Cow::Borrowed("_files"),
+            None => Cow::Borrowed("( )"),
+        };
+
+        let _ = writeln!(
+            buf,
+            "  options+=('{}{}{}:{}')",
+            position,
+            optional,
+            Self::escape_spec(message),
+            action
+        );
+    }
+
+    /// Ranges with at most this many values are offered one by one
+    const MAX_RANGE_VALUES: i64 = 32;
+
+    /// The zsh action completing `opt`'s argument: each value of a small
+    /// integer range, `_files` for file, directory, path or archive
+    /// arguments, and `( )` (nothing to offer) for any other value.
+    fn argument_action(opt: &Opt) -> Cow<'static, str> {
+        if let Some(values) = Self::range_values(opt) {
+            return Cow::Owned(format!("({})", values));
+        }
+        if FILE_PATH_MATCHER.is_match(opt.argument.as_str()) {
+            Cow::Borrowed("_files")
+        } else {
+            Cow::Borrowed("( )")
+        }
+    }
+
+    /// `1 2 3 4 5` for an argument with small integer bounds.
+    fn range_values(opt: &Opt) -> Option<String> {
+        let (low, high) = opt.value_range.as_ref()?;
+        let (Ok(low), Ok(high)) = (low.parse::<i64>(), high.parse::<i64>()) else {
+            return None;
+        };
+        if low > high || high - low >= Self::MAX_RANGE_VALUES {
+            return None;
+        }
+
+        Some(
+            (low..=high)
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
 }
 
 pub struct BashGenerator;
 
 impl BashGenerator {
+    /// Limit for bash-completion's `name:Description` entries
+    pub const MAX_DESC_LEN: usize = 80;
+
     pub fn generate(cmd: &Command) -> EcoString {
         Self::generate_with_compat(cmd, false)
     }
 
     pub fn generate_with_compat(cmd: &Command, bash_completion_compat: bool) -> EcoString {
+        Self::generate_with_options(
+            cmd,
+            &GeneratorOptions {
+                bash_completion_compat,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
+        let bash_completion_compat = options.bash_completion_compat;
         let estimated_size = 512 + cmd.options.len() * 32;
         let mut buf = String::with_capacity(estimated_size);
 
-        let _ = writeln!(buf, "_{}()", cmd.name);
+        let function_name = format!(
+            "{}{}",
+            options.bash_function_prefix.as_deref().unwrap_or("_"),
+            Command::qualified_name(&[cmd.name.as_str()])
+        );
+
+        let _ = writeln!(buf, "{}()", function_name);
         let _ = writeln!(buf, "{{");
         let _ = writeln!(buf, "  local cur prev opts");
         let _ = writeln!(buf, "  COMPREPLY=()");
@@ -180,12 +797,22 @@ impl BashGenerator {
         let _ = writeln!(buf, "  prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"");
         let _ = writeln!(buf);
 
+        if cmd.has_passthrough() {
+            // Words after a literal `--` are passed through, so leave them
+            // to the default completion
+            let _ = writeln!(buf, "  local i");
+            let _ = writeln!(buf, "  for ((i = 1; i < COMP_CWORD; i++)); do");
+            let _ = writeln!(buf, "    [[ ${{COMP_WORDS[i]}} == -- ]] && return 0");
+            let _ = writeln!(buf, "  done");
+            let _ = writeln!(buf);
+        }
+
         // Collect all option strings into a BTreeSet for deduplication and sorting
         let all_opts: BTreeSet<String> = if bash_completion_compat {
             cmd.options
                 .iter()
                 .flat_map(|opt| {
-                    let base_desc = FishGenerator::truncate_after_period(&opt.description);
+                    let base_desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
                     let desc: String = base_desc
                         .split_whitespace()
                         .collect::<Vec<_>>()
@@ -197,7 +824,9 @@ impl BashGenerator {
                         .filter_map(|name| {
                             if matches!(
                                 name.opt_type,
-                                OptNameType::SingleDashAlone | OptNameType::DoubleDashAlone
+                                OptNameType::SingleDashAlone
+                                    | OptNameType::DoubleDashAlone
+                                    | OptNameType::SlashType
                             ) {
                                 None
                             } else if desc.is_empty() {
@@ -214,24 +843,7 @@ impl BashGenerator {
                 })
                 .collect()
         } else {
-            cmd.options
-                .iter()
-                .flat_map(|opt| {
-                    opt.names
-                        .iter()
-                        .filter_map(|name| {
-                            if matches!(
-                                name.opt_type,
-                                OptNameType::SingleDashAlone | OptNameType::DoubleDashAlone
-                            ) {
-                                None
-                            } else {
-                                Some(name.raw.to_string())
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect()
+            Self::flag_words(cmd)
         };
 
         // Build opts string efficiently
@@ -240,6 +852,21 @@ impl BashGenerator {
         let _ = writeln!(buf);
         let _ = writeln!(buf, "  COMPREPLY=($(compgen -W \"${{opts}}\" -- ${{cur}}))");
 
+        if options.bundle_shorts
+            && let Some(shorts) = Self::bundleable_shorts(cmd)
+        {
+            // Offer each flag not yet in the bundle, e.g. `-x` -> `-xv`, `-xf`
+            let _ = writeln!(buf, "  if [[ ${{cur}} =~ ^-[[:alnum:]]+$ ]]; then");
+            let _ = writeln!(buf, "    local shorts=\"{}\" i", shorts);
+            let _ = writeln!(buf, "    for ((i = 0; i < ${{#shorts}}; i++)); do");
+            let _ = writeln!(
+                buf,
+                "      [[ ${{cur}} == *\"${{shorts:i:1}}\"* ]] || COMPREPLY+=(\"${{cur}}${{shorts:i:1}}\")"
+            );
+            let _ = writeln!(buf, "    done");
+            let _ = writeln!(buf, "  fi");
+        }
+
         if bash_completion_compat {
             let _ = writeln!(buf, "  if type __ltrim_colon_completions &>/dev/null; then");
             let _ = writeln!(buf, "    __ltrim_colon_completions \"$cur\"");
@@ -248,31 +875,119 @@ impl BashGenerator {
 
         let _ = writeln!(buf, "}}");
         let _ = writeln!(buf);
+        let complete_opts = if options.bash_nospace {
+            "-o bashdefault -o default -o nospace"
+        } else {
+            "-o bashdefault -o default"
+        };
         let _ = write!(
             buf,
-            "complete -o bashdefault -o default -o nospace -F _{} {}",
-            cmd.name, cmd.name
+            "complete {} -F {} {}",
+            complete_opts,
+            function_name,
+            bash_quote_name(&cmd.name)
         );
+        for name in &options.also_complete {
+            let _ = write!(
+                buf,
+                "\ncomplete {} -F {} {}",
+                complete_opts,
+                function_name,
+                bash_quote_name(name)
+            );
+        }
 
         EcoString::from(buf)
     }
 }
 
+impl BashGenerator {
+    /// Just the word list the completion function hands to `compgen -W`:
+    /// deduplicated, sorted flag names on one line.
+    pub fn generate_compgen(cmd: &Command) -> EcoString {
+        EcoString::from(
+            Self::flag_words(cmd)
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    /// All completable flag names, deduplicated and sorted. Bare `-` and
+    /// `--` and DOS-style `/flag`s are left out.
+    fn flag_words(cmd: &Command) -> BTreeSet<String> {
+        cmd.options
+            .iter()
+            .flat_map(|opt| opt.names.iter())
+            .filter(|name| {
+                !matches!(
+                    name.opt_type,
+                    OptNameType::SingleDashAlone
+                        | OptNameType::DoubleDashAlone
+                        | OptNameType::SlashType
+                )
+            })
+            .map(|name| name.raw.to_string())
+            .collect()
+    }
+
+    /// Letters of all short flags when they can be stacked: there are at
+    /// least two, all alphanumeric, and none takes an argument.
+    fn bundleable_shorts(cmd: &Command) -> Option<String> {
+        let mut letters = String::new();
+        for opt in cmd.options.iter() {
+            for name in opt.names.iter() {
+                if name.opt_type != OptNameType::ShortType {
+                    continue;
+                }
+                let letter = name.raw.chars().nth(1)?;
+                if !opt.argument.is_empty() || !letter.is_ascii_alphanumeric() {
+                    return None;
+                }
+                if !letters.contains(letter) {
+                    letters.push(letter);
+                }
+            }
+        }
+
+        (letters.len() >= 2).then_some(letters)
+    }
+}
+
 pub struct ElvishGenerator;
 
 impl ElvishGenerator {
+    /// Elvish pads candidates into a display column, so keep them short
+    pub const MAX_DESC_LEN: usize = 80;
+
     pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
         let estimated_size = 512 + cmd.options.len() * 48;
         let mut buf = String::with_capacity(estimated_size);
 
         let _ = writeln!(buf, "use builtin;");
         let _ = writeln!(buf, "use str;");
         let _ = writeln!(buf);
-        let _ = writeln!(
-            buf,
-            "set edit:completion:arg-completer[{}] = {{|@words|",
-            cmd.name
-        );
+        match &options.elvish_module {
+            Some(module) => {
+                let _ = writeln!(
+                    buf,
+                    "# use {0}; set edit:completion:arg-completer[{1}] = ${0}:{0}-completions~",
+                    module, cmd.name
+                );
+                let _ = writeln!(buf, "fn {}-completions {{|@words|", module);
+            }
+            None => {
+                let _ = writeln!(
+                    buf,
+                    "set edit:completion:arg-completer[{}] = {{|@words|",
+                    cmd.name
+                );
+            }
+        }
         let _ = writeln!(buf, "    fn spaces {{|n|");
         let _ = writeln!(buf, "        builtin:repeat $n ' ' | str:join ''");
         let _ = writeln!(buf, "    }}");
@@ -293,12 +1008,14 @@ impl ElvishGenerator {
         let _ = writeln!(buf, "        &'{}'= {{", cmd.name);
 
         for opt in cmd.options.iter() {
-            let desc = FishGenerator::truncate_after_period(&opt.description);
+            let desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
             let desc_clean = desc.replace('\'', "");
             for name in opt.names.iter() {
                 if matches!(
                     name.opt_type,
-                    OptNameType::SingleDashAlone | OptNameType::DoubleDashAlone
+                    OptNameType::SingleDashAlone
+                        | OptNameType::DoubleDashAlone
+                        | OptNameType::SlashType
                 ) {
                     continue;
                 }
@@ -318,7 +1035,14 @@ impl ElvishGenerator {
 pub struct NushellGenerator;
 
 impl NushellGenerator {
+    /// Nushell renders descriptions in a wide completion menu
+    pub const MAX_DESC_LEN: usize = 120;
+
     pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
         let estimated_size = 512 + cmd.options.len() * 48;
         let mut buf = String::with_capacity(estimated_size);
 
@@ -366,23 +1090,29 @@ impl NushellGenerator {
         let _ = writeln!(buf, "  export extern {} [", cmd.name);
 
         for opt in cmd.options.iter() {
-            let desc = FishGenerator::truncate_after_period(&opt.description);
-
-            for name in opt.names.iter() {
-                if matches!(
-                    name.opt_type,
-                    OptNameType::SingleDashAlone | OptNameType::DoubleDashAlone
-                ) {
-                    continue;
-                }
+            let desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
 
-                if opt.argument.is_empty() {
-                    let _ = writeln!(buf, "    {} # {}", name.raw, desc);
+            for flag in Self::signature_flags(opt) {
+                if opt.argument.is_empty() && desc.is_empty() {
+                    let _ = writeln!(buf, "    {}", flag);
+                } else if opt.argument.is_empty() {
+                    let _ = writeln!(buf, "    {} # {}", flag, desc);
+                } else if desc.is_empty() {
+                    let _ = writeln!(
+                        buf,
+                        "    {}: {}  # {}",
+                        flag,
+                        Self::argument_type(&opt.argument),
+                        opt.argument
+                    );
                 } else {
                     let _ = writeln!(
                         buf,
-                        "    {}: string  # {} # {}",
-                        name.raw, opt.argument, desc
+                        "    {}: {}  # {} # {}",
+                        flag,
+                        Self::argument_type(&opt.argument),
+                        opt.argument,
+                        desc
                     );
                 }
             }
@@ -396,18 +1126,997 @@ impl NushellGenerator {
 
         EcoString::from(buf)
     }
+
+    /// The signature flags for `opt`: its first long and short names paired
+    /// as `--long(-s)`, so Nushell sees one parameter, then any further
+    /// names on their own. Signatures only accept `-s` and `--long` flags;
+    /// old-style `-name` options stay in the options list.
+    fn signature_flags(opt: &Opt) -> Vec<EcoString> {
+        let mut longs = opt
+            .names
+            .iter()
+            .filter(|name| name.opt_type == OptNameType::LongType);
+        let mut shorts = opt
+            .names
+            .iter()
+            .filter(|name| name.opt_type == OptNameType::ShortType);
+
+        let mut flags = Vec::new();
+        match (longs.next(), shorts.next()) {
+            (Some(long), Some(short)) => {
+                flags.push(eco_format!("{}({})", long.raw, short.raw));
+            }
+            (Some(name), None) | (None, Some(name)) => flags.push(name.raw.clone()),
+            (None, None) => {}
+        }
+        flags.extend(longs.chain(shorts).map(|name| name.raw.clone()));
+        flags
+    }
+
+    /// Infer a Nushell type from an argument hint such as `FILE` or `<N>`,
+    /// falling back to `string`.
+    pub fn argument_type(argument: &str) -> &'static str {
+        if FILE_PATH_MATCHER.is_match(argument) {
+            return "path";
+        }
+
+        let hint = argument
+            .trim_matches(|c: char| !c.is_ascii_alphanumeric())
+            .to_ascii_uppercase();
+        match hint.as_str() {
+            "N" | "NUM" | "NUMBER" | "COUNT" | "PORT" | "INT" | "INTEGER" => "int",
+            "DURATION" => "duration",
+            _ => "string",
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct PowerShellGenerator;
 
-    #[test]
-    fn test_truncate_after_period() {
-        let text = "This is a description. With more text.";
-        assert_eq!(
-            FishGenerator::truncate_after_period(text),
-            "This is a description"
-        );
+impl PowerShellGenerator {
+    /// PowerShell shows descriptions as tooltips, so there is room for more text
+    pub const MAX_DESC_LEN: usize = 120;
+
+    pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
+        let estimated_size = 1024 + cmd.options.len() * 128;
+        let mut buf = String::with_capacity(estimated_size);
+        let name = powershell_quote(&cmd.name);
+
+        let _ = writeln!(buf, "using namespace System.Management.Automation");
+        let _ = writeln!(buf, "using namespace System.Management.Automation.Language");
+        let _ = writeln!(buf);
+        let _ = writeln!(
+            buf,
+            "Register-ArgumentCompleter -Native -CommandName '{}' -ScriptBlock {{",
+            name
+        );
+        let _ = writeln!(
+            buf,
+            "    param($wordToComplete, $commandAst, $cursorPosition)"
+        );
+        let _ = writeln!(buf);
+        // Join the leading bare words into a key such as `git;remote` to pick
+        // the parameter set of the innermost subcommand
+        let _ = writeln!(buf, "    $commandElements = $commandAst.CommandElements");
+        let _ = writeln!(buf, "    $command = @(");
+        let _ = writeln!(buf, "        '{}'", name);
+        let _ = writeln!(
+            buf,
+            "        for ($i = 1; $i -lt $commandElements.Count; $i++) {{"
+        );
+        let _ = writeln!(buf, "            $element = $commandElements[$i]");
+        let _ = writeln!(
+            buf,
+            "            if ($element -isnot [StringConstantExpressionAst] -or"
+        );
+        let _ = writeln!(
+            buf,
+            "                $element.StringConstantType -ne [StringConstantType]::BareWord -or"
+        );
+        let _ = writeln!(buf, "                $element.Value.StartsWith('-') -or");
+        let _ = writeln!(
+            buf,
+            "                $element.Value -eq $wordToComplete) {{"
+        );
+        let _ = writeln!(buf, "                break");
+        let _ = writeln!(buf, "            }}");
+        let _ = writeln!(buf, "            $element.Value");
+        let _ = writeln!(buf, "        }}) -join ';'");
+        let _ = writeln!(buf);
+        let _ = writeln!(buf, "    $completions = @(switch ($command) {{");
+
+        Self::write_parameter_set(&mut buf, &name, cmd, options);
+
+        let _ = writeln!(buf, "    }})");
+        let _ = writeln!(buf);
+        let _ = writeln!(
+            buf,
+            "    $completions.Where{{ $_.CompletionText -like \"$wordToComplete*\" }} |"
+        );
+        let _ = writeln!(buf, "        Sort-Object -Property ListItemText");
+        let _ = write!(buf, "}}");
+
+        EcoString::from(buf)
+    }
+
+    /// Write the `switch` case offering `cmd`'s options and subcommands, then
+    /// recurse so each subcommand gets its own parameter set.
+    fn write_parameter_set(buf: &mut String, key: &str, cmd: &Command, options: &GeneratorOptions) {
+        let _ = writeln!(buf, "        '{}' {{", key);
+
+        for opt in cmd.options.iter() {
+            let desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
+            for name in opt.names.iter() {
+                if matches!(
+                    name.opt_type,
+                    OptNameType::SingleDashAlone
+                        | OptNameType::DoubleDashAlone
+                        | OptNameType::SlashType
+                ) {
+                    continue;
+                }
+                Self::write_result(buf, &name.raw, "ParameterName", desc);
+            }
+        }
+
+        for sub in cmd.subcommands.iter() {
+            let desc = options.menu_description(&sub.description, Self::MAX_DESC_LEN);
+            Self::write_result(buf, &sub.name, "ParameterValue", desc);
+        }
+
+        let _ = writeln!(buf, "            break");
+        let _ = writeln!(buf, "        }}");
+
+        for sub in cmd.subcommands.iter() {
+            let sub_key = format!("{};{}", key, powershell_quote(&sub.name));
+            Self::write_parameter_set(buf, &sub_key, sub, options);
+        }
+    }
+
+    fn write_result(buf: &mut String, text: &str, result_type: &str, desc: &str) {
+        let text = powershell_quote(text);
+        // CompletionResult rejects an empty tooltip
+        let tooltip = if desc.is_empty() {
+            text.clone()
+        } else {
+            powershell_quote(desc)
+        };
+        let _ = writeln!(
+            buf,
+            "            [CompletionResult]::new('{0}', '{0}', [CompletionResultType]::{1}, '{2}')",
+            text, result_type, tooltip
+        );
+    }
+}
+
+/// Tab-separated summary with one row per option and per subcommand.
+///
+/// Option rows are `names<TAB>argument<TAB>description`; subcommand rows are
+/// `subcommand<TAB>path<TAB>description`. Every row has exactly three fields.
+pub struct TsvGenerator;
+
+impl TsvGenerator {
+    pub fn generate(cmd: &Command) -> EcoString {
+        let mut buf = String::with_capacity(64 + cmd.options.len() * 64);
+
+        for opt in cmd.options.iter() {
+            let names = opt.names_joined(",");
+            Self::write_row(&mut buf, [&names, &opt.argument, &opt.description]);
+        }
+
+        Self::write_subcommands(&mut buf, &[], cmd);
+        EcoString::from(buf)
+    }
+
+    fn write_subcommands(buf: &mut String, path: &[&str], cmd: &Command) {
+        for sub in cmd.subcommands.iter() {
+            let mut sub_path = path.to_vec();
+            sub_path.push(&sub.name);
+            Self::write_row(buf, ["subcommand", &sub_path.join(" "), &sub.description]);
+            Self::write_subcommands(buf, &sub_path, sub);
+        }
+    }
+
+    fn write_row(buf: &mut String, fields: [&str; 3]) {
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                buf.push('\t');
+            }
+            buf.push_str(&Self::escape(field));
+        }
+        buf.push('\n');
+    }
+
+    /// Escape characters that would break the row/column structure.
+    pub fn escape(field: &str) -> Cow<'_, str> {
+        if !field.contains(['\\', '\t', '\n', '\r']) {
+            return Cow::Borrowed(field);
+        }
+        let mut out = String::with_capacity(field.len() + 4);
+        for c in field.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                c => out.push(c),
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Plain listing of a command's name, description, usage, options and
+/// subcommands, one block per item.
+pub struct NativeGenerator;
+
+impl NativeGenerator {
+    pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_width(cmd, None)
+    }
+
+    /// Render with the description, option and subcommand lines wrapped to
+    /// `max_width` columns. Usage is kept verbatim.
+    pub fn generate_with_width(cmd: &Command, max_width: Option<usize>) -> EcoString {
+        let mut output = Vec::new();
+
+        output.push(format!("Name:  {}", cmd.name));
+        output.push(Self::wrapped("Desc:  ", &cmd.description, max_width));
+        output.push(format!("Usage:\n{}", cmd.usage));
+
+        for opt in cmd.options.iter() {
+            let line = format!("{} ({})", opt.names_joined(", "), opt.argument);
+            output.push(Self::wrapped("  ", &line, max_width));
+        }
+
+        for subcmd in cmd.subcommands.iter() {
+            output.push(Self::wrapped("Subcommand: ", &subcmd.name, max_width));
+        }
+
+        EcoString::from(output.join("\n\n"))
+    }
+
+    /// `prefix` and `text`, with `text` wrapped to `max_width` columns and
+    /// continuation lines indented under its start.
+    fn wrapped(prefix: &str, text: &str, max_width: Option<usize>) -> String {
+        match max_width {
+            Some(width) => {
+                let indent = format!("\n{}", " ".repeat(prefix.len()));
+                let lines = wrap_words(text, width.saturating_sub(prefix.len()).max(1));
+                format!("{}{}", prefix, lines.join(&indent))
+            }
+            None => format!("{}{}", prefix, text),
+        }
+    }
+}
+
+/// Markdown reference page: a title, the description and usage, then
+/// bullet lists of options and subcommands.
+pub struct MarkdownGenerator;
+
+impl MarkdownGenerator {
+    pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_width(cmd, None)
+    }
+
+    /// Render with description paragraphs and list items wrapped to
+    /// `max_width` columns. Usage is kept verbatim in its code block.
+    pub fn generate_with_width(cmd: &Command, max_width: Option<usize>) -> EcoString {
+        let mut buf = String::with_capacity(128 + cmd.options.len() * 80);
+        let _ = writeln!(buf, "# {}", cmd.name);
+
+        if !cmd.description.is_empty() {
+            buf.push('\n');
+            Self::write_wrapped(&mut buf, &cmd.description, "", "", max_width);
+        }
+        if !cmd.usage.trim().is_empty() {
+            let _ = write!(buf, "\n## Usage\n\n```text\n{}\n```\n", cmd.usage.trim());
+        }
+
+        if !cmd.options.is_empty() {
+            buf.push_str("\n## Options\n\n");
+            for opt in cmd.options.iter() {
+                let mut item = opt.names_joined(", ");
+                if !opt.argument.is_empty() {
+                    item.push(' ');
+                    item.push_str(&opt.argument);
+                }
+                Self::write_item(&mut buf, &item, &opt.description, max_width);
+            }
+        }
+
+        if !cmd.subcommands.is_empty() {
+            buf.push_str("\n## Subcommands\n\n");
+            for sub in cmd.subcommands.iter() {
+                Self::write_item(&mut buf, &sub.name, &sub.description, max_width);
+            }
+        }
+
+        EcoString::from(buf)
+    }
+
+    fn write_item(buf: &mut String, code: &str, description: &str, max_width: Option<usize>) {
+        let mut text = format!("`{}`", code);
+        if !description.is_empty() {
+            text.push_str(": ");
+            text.push_str(description);
+        }
+        Self::write_wrapped(buf, &text, "- ", "  ", max_width);
+    }
+
+    fn write_wrapped(
+        buf: &mut String,
+        text: &str,
+        first: &str,
+        rest: &str,
+        max_width: Option<usize>,
+    ) {
+        let lines = match max_width {
+            Some(width) => wrap_words(text, width.saturating_sub(first.len()).max(1)),
+            None => vec![text.split_whitespace().collect::<Vec<_>>().join(" ")],
+        };
+        for (i, line) in lines.iter().enumerate() {
+            buf.push_str(if i == 0 { first } else { rest });
+            buf.push_str(line);
+            buf.push('\n');
+        }
+    }
+}
+
+/// YAML spec for the carapace completion engine, with flags as a map from
+/// `-s, --long=` style keys to descriptions. Scalars are written as
+/// double-quoted strings, so any text is valid YAML.
+pub struct CarapaceGenerator;
+
+impl CarapaceGenerator {
+    /// Carapace shows descriptions in a menu, like zsh
+    pub const MAX_DESC_LEN: usize = 120;
+
+    pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
+        let mut buf = String::with_capacity(128 + cmd.options.len() * 64);
+        Self::write_command(&mut buf, cmd, "", "", options);
+        EcoString::from(buf)
+    }
+
+    /// Write `cmd`'s keys. The first key is prefixed with `first` (`- ` for
+    /// list items) and the rest with `indent`.
+    fn write_command(
+        buf: &mut String,
+        cmd: &Command,
+        first: &str,
+        indent: &str,
+        options: &GeneratorOptions,
+    ) {
+        let _ = writeln!(buf, "{}name: {}", first, Self::quote(&cmd.name));
+        if !cmd.description.is_empty() {
+            let desc = options.menu_description(&cmd.description, Self::MAX_DESC_LEN);
+            let _ = writeln!(buf, "{}description: {}", indent, Self::quote(desc));
+        }
+
+        let flags: Vec<(&Opt, Vec<&str>)> = cmd
+            .options
+            .iter()
+            .map(|opt| (opt, Self::flag_names(opt)))
+            .filter(|(_, names)| !names.is_empty())
+            .collect();
+        if !flags.is_empty() {
+            let _ = writeln!(buf, "{}flags:", indent);
+            for (opt, names) in flags {
+                let desc = options.menu_description(&opt.description, Self::MAX_DESC_LEN);
+                Self::write_flag(buf, indent, opt, &names, desc);
+            }
+        }
+
+        if !cmd.subcommands.is_empty() {
+            let _ = writeln!(buf, "{}commands:", indent);
+            let nested = format!("{}    ", indent);
+            let item = format!("{}  - ", indent);
+            for sub in cmd.subcommands.iter() {
+                Self::write_command(buf, sub, &item, &nested, options);
+            }
+        }
+    }
+
+    /// `"-o, --output*=": "desc"`: `*` marks a repeatable flag, `=` a
+    /// required value and `?` an optional one.
+    fn write_flag(buf: &mut String, indent: &str, opt: &Opt, names: &[&str], desc: &str) {
+        let mut key = names.join(", ");
+        if opt.multiple {
+            key.push('*');
+        }
+        if !opt.argument.is_empty() {
+            key.push(if opt.optional_argument { '?' } else { '=' });
+        }
+        let _ = writeln!(
+            buf,
+            "{}  {}: {}",
+            indent,
+            Self::quote(&key),
+            Self::quote(desc)
+        );
+    }
+
+    /// Names carapace can complete: `-s`, `--long` and `-name` styles.
+    fn flag_names(opt: &Opt) -> Vec<&str> {
+        opt.names
+            .iter()
+            .filter(|name| {
+                matches!(
+                    name.opt_type,
+                    OptNameType::ShortType | OptNameType::LongType | OptNameType::OldType
+                )
+            })
+            .map(|name| name.raw.as_str())
+            .collect()
+    }
+
+    fn quote(text: &str) -> String {
+        serde_json::to_string(text).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ecow::EcoVec;
+
+    fn single_opt_command(opt_name: &str, argument: &str, description: &str) -> Command {
+        let mut cmd = Command::new(EcoString::from("test"));
+        cmd.options.push(Opt {
+            names: {
+                let mut v = EcoVec::new();
+                v.push(OptName::from_text(opt_name).unwrap());
+                v
+            },
+            argument: EcoString::from(argument),
+            description: EcoString::from(description),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+        cmd
+    }
+
+    #[test]
+    fn test_slash_flags_only_in_shells_that_take_them() {
+        let mut cmd = Command::new(EcoString::from("robocopy"));
+        cmd.options = crate::parser::Parser::parse_line_with("  /mir      Mirror a tree", true);
+
+        assert!(FishGenerator::generate(&cmd).contains("'/mir'"));
+        for output in [
+            ZshGenerator::generate(&cmd),
+            BashGenerator::generate(&cmd),
+            ElvishGenerator::generate(&cmd),
+            PowerShellGenerator::generate(&cmd),
+            CarapaceGenerator::generate(&cmd),
+        ] {
+            assert!(!output.contains("/mir"), "{}", output);
+        }
+    }
+
+    #[test]
+    fn test_reserved_and_special_command_names() {
+        let mut cmd = single_opt_command("--force", "", "Force it");
+        cmd.name = EcoString::from("end");
+        let mut sub = single_opt_command("--all", "", "All of it");
+        sub.name = EcoString::from("end");
+        cmd.subcommands.push(sub);
+
+        let fish = FishGenerator::generate(&cmd);
+        assert!(fish.contains("complete -c 'end' -l 'force'"));
+        assert!(fish.contains("complete -c end_end -l 'all'"));
+
+        cmd.name = EcoString::from("my tool");
+        let bash = BashGenerator::generate(&cmd);
+        assert!(bash.starts_with("_my_tool()"));
+        assert!(bash.ends_with("-F _my_tool 'my tool'"));
+
+        cmd.name = EcoString::from("end");
+        let bash = BashGenerator::generate(&cmd);
+        assert!(bash.ends_with("-F _end end"));
+    }
+
+    #[test]
+    fn test_old_type_option_per_generator() {
+        let cmd = single_opt_command("-display", "DISPLAY", "X server to use");
+        assert_eq!(cmd.options[0].names[0].opt_type, OptNameType::OldType);
+
+        let bash = BashGenerator::generate(&cmd);
+        assert!(bash.contains("opts=\"-display\""));
+
+        let zsh = ZshGenerator::generate(&cmd);
+        assert!(zsh.contains("options+=('-display[DISPLAY X server to use]:DISPLAY:( )')"));
+
+        let fish = FishGenerator::generate(&cmd);
+        assert!(fish.contains("complete -c test -o 'display' -x -d 'X server to use'"));
+
+        let elvish = ElvishGenerator::generate(&cmd);
+        assert!(elvish.contains("cand -display 'X server to use'"));
+
+        let nushell = NushellGenerator::generate(&cmd);
+        assert!(nushell.contains("[ \"-display\" ]"));
+        assert!(!nushell.contains("    -display"));
+
+        let powershell = PowerShellGenerator::generate(&cmd);
+        assert!(powershell.contains("'-display'"));
+    }
+
+    #[test]
+    fn test_zsh_old_type_optional_argument() {
+        let mut cmd = single_opt_command("-color", "WHEN", "Colorize output");
+        cmd.options.make_mut()[0].optional_argument = true;
+        let zsh = ZshGenerator::generate(&cmd);
+        assert!(zsh.contains("options+=('-color=-[Colorize output]::WHEN:')"));
+    }
+
+    #[test]
+    fn test_qualified_subcommand_names_agree() {
+        let mut add = single_opt_command("--track", "", "Track branch");
+        add.name = EcoString::from("add");
+        let mut remote = single_opt_command("--verbose", "", "Be verbose");
+        remote.name = EcoString::from("remote");
+        remote.subcommands.push(add);
+        let mut git = single_opt_command("--version", "", "Show version");
+        git.name = EcoString::from("git");
+        git.subcommands.push(remote);
+
+        let fish = FishGenerator::generate(&git);
+        assert!(fish.contains("complete -c git_remote -l 'verbose'"));
+        assert!(fish.contains("complete -c git_remote_add -l 'track'"));
+
+        let mut renamed = git.clone();
+        renamed.rename_subcommands_with_parent();
+        let remote = &renamed.subcommands[0];
+        let add = &remote.subcommands[0];
+        for sub in [remote, add] {
+            let function = format!("_{}()", sub.name);
+            assert!(fish.contains(&format!("complete -c {} ", sub.name)));
+            assert!(BashGenerator::generate(sub).starts_with(&function));
+            assert!(ZshGenerator::generate(sub).contains(&format!("{} {{", function)));
+        }
+    }
+
+    #[test]
+    fn test_description_limits_per_shell() {
+        let long_desc = "x".repeat(200);
+        let cmd = single_opt_command("--long", "", &long_desc);
+
+        let fish = FishGenerator::generate(&cmd);
+        assert!(fish.contains(&format!("-d '{}'", "x".repeat(80))));
+        assert!(!fish.contains(&"x".repeat(81)));
+
+        let zsh = ZshGenerator::generate(&cmd);
+        assert!(zsh.contains(&format!("[{}]", "x".repeat(120))));
+        assert!(!zsh.contains(&"x".repeat(121)));
+
+        let options = GeneratorOptions {
+            max_desc_len: Some(10),
+            ..Default::default()
+        };
+        let zsh_short = ZshGenerator::generate_with_options(&cmd, &options);
+        assert!(zsh_short.contains(&format!("[{}]", "x".repeat(10))));
+    }
+
+    #[test]
+    fn test_bash_bundle_shorts_only_for_boolean_flags() {
+        let mut cmd = single_opt_command("-x", "", "Extract");
+        cmd.options
+            .push(single_opt_command("-v", "", "Be verbose").options[0].clone());
+        cmd.options
+            .push(single_opt_command("--level", "N", "Set level").options[0].clone());
+        let options = GeneratorOptions {
+            bundle_shorts: true,
+            ..Default::default()
+        };
+
+        let bash = BashGenerator::generate_with_options(&cmd, &options);
+        assert!(bash.contains("local shorts=\"xv\" i"));
+        assert!(!BashGenerator::generate(&cmd).contains("local shorts"));
+
+        let mut cmd_with_arg = cmd.clone();
+        cmd_with_arg
+            .options
+            .push(single_opt_command("-f", "FILE", "Archive file").options[0].clone());
+        let bash = BashGenerator::generate_with_options(&cmd_with_arg, &options);
+        assert!(!bash.contains("local shorts"));
+    }
+
+    #[test]
+    fn test_bash_compgen_word_list() {
+        let mut cmd = single_opt_command("--verbose", "", "Be verbose");
+        cmd.options
+            .push(single_opt_command("-a", "", "All").options[0].clone());
+        cmd.options
+            .push(single_opt_command("--verbose", "", "Duplicate").options[0].clone());
+        cmd.options
+            .push(single_opt_command("--", "", "End of options").options[0].clone());
+
+        let words = BashGenerator::generate_compgen(&cmd);
+        assert_eq!(words.as_str(), "--verbose -a");
+        assert_eq!(words.lines().count(), 1);
+        assert!(!words.contains("()") && !words.contains("complete"));
+        assert!(BashGenerator::generate(&cmd).contains(&format!("opts=\"{}\"", words)));
+    }
+
+    #[test]
+    fn test_passthrough_stops_flag_completion() {
+        let mut cmd = single_opt_command("--verbose", "", "Be verbose");
+        let plain = cmd.clone();
+        cmd.options
+            .push(single_opt_command("--", "", "Pass the rest through").options[0].clone());
+
+        let fish = FishGenerator::generate(&cmd);
+        assert_eq!(
+            fish,
+            "complete -c test -n 'not contains -- -- (commandline -opc)' -l 'verbose'  -d 'Be verbose'"
+        );
+        assert!(!FishGenerator::generate(&plain).contains("commandline"));
+
+        let bash = BashGenerator::generate(&cmd);
+        assert!(bash.contains("[[ ${COMP_WORDS[i]} == -- ]] && return 0"));
+        assert!(!BashGenerator::generate(&plain).contains("COMP_WORDS[i]"));
+
+        // `_arguments -S` already ignores options after `--`
+        assert!(ZshGenerator::generate(&cmd).contains("_arguments -s -S"));
+    }
+
+    #[test]
+    fn test_carapace_flag_modifiers() {
+        let mut cmd = single_opt_command("--color", "WHEN", "Colorize");
+        cmd.options.make_mut()[0].optional_argument = true;
+        let mut include = single_opt_command("-I", "DIR", "Add include dir").options[0].clone();
+        include.multiple = true;
+        cmd.options.push(include);
+
+        let spec = CarapaceGenerator::generate(&cmd);
+        assert!(spec.contains("\n  \"--color?\": \"Colorize\"\n"));
+        assert!(spec.contains("\n  \"-I*=\": \"Add include dir\"\n"));
+    }
+
+    #[test]
+    fn test_zsh_value_range_hint() {
+        let level = crate::parser::Parser::parse_line("  --level 1-5      Set the level");
+        let pct = crate::parser::Parser::parse_line("  --pct 0..100      Percentage");
+        let mut cmd = Command::new(EcoString::from("test"));
+        cmd.options.push(level[0].clone());
+        cmd.options.push(pct[0].clone());
+
+        let zsh = ZshGenerator::generate(&cmd);
+        assert!(zsh.contains("options+=('--level[1-5 Set the level]:1-5:(1 2 3 4 5)')"));
+        // Too many values to list
+        assert!(zsh.contains("options+=('--pct[0..100 Percentage]:0..100:( )')"));
+    }
+
+    #[test]
+    fn test_also_complete_registers_wrappers() {
+        let cmd = single_opt_command("--force", "", "Force it");
+        let options = GeneratorOptions {
+            also_complete: vec![EcoString::from("mt"), EcoString::from("test-wrapper")],
+            ..Default::default()
+        };
+
+        let bash = BashGenerator::generate_with_options(&cmd, &options);
+        assert!(bash.contains("-F _test test\n"));
+        assert!(bash.contains("complete -o bashdefault -o default -o nospace -F _test mt\n"));
+        assert!(bash.ends_with("-F _test test-wrapper"));
+
+        let zsh = ZshGenerator::generate_with_options(&cmd, &options);
+        assert_eq!(zsh.lines().next(), Some("#compdef test mt test-wrapper"));
+
+        let fish = FishGenerator::generate_with_options(&cmd, &options);
+        assert!(fish.contains("complete -c mt -w test"));
+    }
+
+    #[test]
+    fn test_zsh_compdef_name_override() {
+        let cmd = single_opt_command("--verbose", "", "Be verbose");
+        let options = GeneratorOptions {
+            zsh_compdef_name: Some(EcoString::from("test-alias")),
+            ..Default::default()
+        };
+
+        let zsh = ZshGenerator::generate_with_options(&cmd, &options);
+        assert_eq!(zsh.lines().next(), Some("#compdef test-alias"));
+        assert!(zsh.contains("_test() {"));
+        assert!(ZshGenerator::generate(&cmd).starts_with("#compdef test\n"));
+    }
+
+    #[test]
+    fn test_description_modes() {
+        let cmd = single_opt_command(
+            "--out",
+            "",
+            "Write to out.txt, e.g. for logs. Overwrites existing files.",
+        );
+        let with_mode = |description_mode| GeneratorOptions {
+            description_mode,
+            ..Default::default()
+        };
+
+        let first = with_mode(DescriptionMode::FirstSentence);
+        let fish = FishGenerator::generate_with_options(&cmd, &first);
+        assert!(fish.ends_with("-d 'Write to out.txt, e.g. for logs'"));
+        let zsh = ZshGenerator::generate_with_options(&cmd, &first);
+        assert!(zsh.contains("'--out[Write to out.txt, e.g. for logs]'"));
+
+        let full = with_mode(DescriptionMode::Full);
+        let fish = FishGenerator::generate_with_options(&cmd, &full);
+        assert!(fish.ends_with("-d 'Write to out.txt, e.g. for logs. Overwrites existing files.'"));
+        let nushell = NushellGenerator::generate_with_options(&cmd, &full);
+        assert!(
+            nushell.contains("--out # Write to out.txt, e.g. for logs. Overwrites existing files.")
+        );
+
+        let none = with_mode(DescriptionMode::None);
+        let fish = FishGenerator::generate_with_options(&cmd, &none);
+        assert!(fish.ends_with("complete -c test -l 'out' "));
+        assert!(!fish.contains("-d"));
+        let zsh = ZshGenerator::generate_with_options(&cmd, &none);
+        assert!(zsh.contains("options+=('--out')"));
+        let bash = BashGenerator::generate_with_options(
+            &cmd,
+            &GeneratorOptions {
+                bash_completion_compat: true,
+                ..none
+            },
+        );
+        assert!(bash.contains("opts=\"--out\""));
+    }
+
+    #[test]
+    fn test_first_sentence() {
+        assert_eq!(first_sentence("Print help. More text."), "Print help");
+        assert_eq!(
+            first_sentence("Set level to 1.5 or higher"),
+            "Set level to 1.5 or higher"
+        );
+        assert_eq!(first_sentence("Wait... then go"), "Wait");
+        assert_eq!(first_sentence("Ends with a period."), "Ends with a period");
+    }
+
+    #[test]
+    fn test_truncate_after_period() {
+        let text = "This is a description. With more text.";
+        assert_eq!(
+            FishGenerator::truncate_after_period(text),
+            "This is a description"
+        );
+        assert_eq!(
+            FishGenerator::truncate_after_period("na\u{ef}ve.more"),
+            "na\u{ef}ve"
+        );
+    }
+
+    #[test]
+    fn test_slice_to_char_boundary() {
+        let text = "na\u{ef}ve";
+        // Byte 3 is inside the two-byte 'ï'
+        assert_eq!(slice_to_char_boundary(text, 3), "na");
+        assert_eq!(slice_to_char_boundary(text, 4), "na\u{ef}");
+        assert_eq!(slice_to_char_boundary(text, 0), "");
+        assert_eq!(slice_to_char_boundary(text, 100), text);
+    }
+
+    #[test]
+    fn test_zsh_optional_argument_spec() {
+        let mut cmd = single_opt_command("--color", "WHEN", "Colorize output");
+        cmd.options.make_mut()[0].optional_argument = true;
+        let out = ZshGenerator::generate(&cmd);
+        assert!(out.contains("options+=('--color=-[Colorize output]::WHEN:')"));
+
+        let required = ZshGenerator::generate(&single_opt_command("--color", "WHEN", "Colorize"));
+        assert!(required.contains("options+=('--color[WHEN Colorize]:WHEN:( )')"));
+    }
+
+    #[test]
+    fn test_zsh_positionals() {
+        let mut cmd = Command::new(EcoString::from("tool"));
+        for (name, description) in [
+            ("<FILE>", "The input file"),
+            ("[MODE]", ""),
+            ("{fast,slow}", "Speed: fast or slow"),
+            ("<NAME>...", "Names"),
+        ] {
+            cmd.positionals.push(Positional {
+                name: EcoString::from(name),
+                description: EcoString::from(description),
+            });
+        }
+
+        let zsh = ZshGenerator::generate(&cmd);
+        assert!(
+            zsh.contains("  options+=('1:The input file:_files')\n"),
+            "{}",
+            zsh
+        );
+        assert!(zsh.contains("  options+=('2::MODE:( )')\n"));
+        assert!(zsh.contains("  options+=('3:Speed\\: fast or slow:(fast slow)')\n"));
+        assert!(zsh.contains("  options+=('*:Names:( )')\n"));
+    }
+
+    #[test]
+    fn test_nushell_argument_types() {
+        let output = NushellGenerator::generate(&single_opt_command("--output", "FILE", "Write"));
+        assert!(output.contains("--output: path  # FILE # Write"));
+        let jobs = NushellGenerator::generate(&single_opt_command("--jobs", "N", "Jobs"));
+        assert!(jobs.contains("--jobs: int  # N # Jobs"));
+
+        assert_eq!(NushellGenerator::argument_type("<PORT>"), "int");
+        assert_eq!(NushellGenerator::argument_type("DURATION"), "duration");
+        assert_eq!(NushellGenerator::argument_type("WHEN"), "string");
+    }
+
+    #[test]
+    fn test_nushell_pairs_short_and_long_names() {
+        let mut cmd = single_opt_command("--verbose", "", "Verbose output");
+        let mut names = cmd.options[0].names.clone();
+        names.push(OptName::new(EcoString::from("-v"), OptNameType::ShortType));
+        cmd.options.make_mut()[0].names = names;
+        cmd.options
+            .push(single_opt_command("-o", "FILE", "Output").options[0].clone());
+
+        let output = NushellGenerator::generate(&cmd);
+        let flags: Vec<&str> = output
+            .lines()
+            .filter(|l| l.trim_start().starts_with('-'))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                "    --verbose(-v) # Verbose output",
+                "    -o: path  # FILE # Output"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tsv_rows_and_escaping() {
+        let mut cmd = single_opt_command("--output", "FILE", "Write\tto\nFILE");
+        let mut sub = single_opt_command("--all", "", "All");
+        sub.name = EcoString::from("remote");
+        sub.description = EcoString::from("Manage remotes");
+        let mut nested = Command::new(EcoString::from("add"));
+        nested.description = EcoString::from("Add a remote");
+        sub.subcommands.push(nested);
+        cmd.subcommands.push(sub);
+
+        let out = TsvGenerator::generate(&cmd);
+        let rows: Vec<&str> = out.lines().collect();
+        assert_eq!(rows.len(), 3);
+        for row in &rows {
+            assert_eq!(row.split('\t').count(), 3, "row: {:?}", row);
+        }
+        assert_eq!(rows[0], "--output\tFILE\tWrite\\tto\\nFILE");
+        assert_eq!(rows[1], "subcommand\tremote\tManage remotes");
+        assert_eq!(rows[2], "subcommand\tremote add\tAdd a remote");
+    }
+
+    #[test]
+    fn test_format_native_includes_fields() {
+        let mut cmd = Command::new(EcoString::from("test"));
+        cmd.description = EcoString::from("Test command");
+        cmd.usage = EcoString::from("test [OPTIONS]");
+
+        cmd.options.push(crate::types::Opt {
+            names: {
+                let mut v = EcoVec::new();
+                v.push(crate::types::OptName::new(
+                    EcoString::from("-v"),
+                    crate::types::OptNameType::ShortType,
+                ));
+                v.push(crate::types::OptName::new(
+                    EcoString::from("--verbose"),
+                    crate::types::OptNameType::LongType,
+                ));
+                v
+            },
+            argument: EcoString::from("FILE"),
+            description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        cmd.subcommands.push(Command {
+            name: EcoString::from("sub"),
+            description: EcoString::new(),
+            usage: EcoString::new(),
+            options: EcoVec::new(),
+            positionals: EcoVec::new(),
+            subcommands: EcoVec::new(),
+            version: EcoString::new(),
+            metadata: None,
+        });
+
+        let out = NativeGenerator::generate(&cmd);
+        assert!(out.contains("Name:  test"));
+        assert!(out.contains("Desc:  Test command"));
+        assert!(out.contains("Usage:\ntest [OPTIONS]"));
+        assert!(out.contains("-v, --verbose"));
+        assert!(out.contains("Subcommand: sub"));
+    }
+
+    #[test]
+    fn test_output_format_dispatch() {
+        for name in ["bash", "zsh", "fish", "json", "native", "summary"] {
+            let format: OutputFormat = name.parse().unwrap();
+            if name != "summary" {
+                assert_eq!(format.as_str(), name);
+            }
+        }
+        assert_eq!(
+            "SUMMARY".parse::<OutputFormat>().unwrap(),
+            OutputFormat::Tsv
+        );
+        assert!("ksh".parse::<OutputFormat>().is_err());
+
+        let cmd = single_opt_command("--verbose", "", "Verbose");
+        assert_eq!(
+            generate(OutputFormat::Fish, &cmd),
+            FishGenerator::generate(&cmd)
+        );
+        assert_eq!(
+            generate(OutputFormat::Json, &cmd),
+            JsonGenerator::generate(&cmd)
+        );
+    }
+
+    #[test]
+    fn test_markdown_wraps_to_max_line_width() {
+        let long = "Write the generated output to FILE instead of standard output, \
+                    creating parent directories as needed and replacing any existing file";
+        let cmd = single_opt_command("--output", "FILE", long);
+
+        let out = MarkdownGenerator::generate_with_width(&cmd, Some(40));
+        let items: Vec<&str> = out
+            .lines()
+            .skip_while(|line| *line != "## Options")
+            .skip(2)
+            .collect();
+        assert!(items.len() > 1, "expected wrapped item: {:?}", out);
+        assert!(items[0].starts_with("- `--output FILE`: Write"));
+        for line in &items {
+            assert!(line.chars().count() <= 40, "line too long: {:?}", line);
+        }
+        assert!(items[1..].iter().all(|line| line.starts_with("  ")));
+
+        let unwrapped = MarkdownGenerator::generate(&cmd);
+        assert!(unwrapped.contains(&format!("- `--output FILE`: {}", long)));
+    }
+
+    #[test]
+    fn test_native_wraps_option_lines_to_max_line_width() {
+        let mut cmd = single_opt_command("--output", "FILE", "Output");
+        cmd.description =
+            EcoString::from("Convert help text into shell completions and other formats");
+        for name in ["-o", "--out", "--write-output-to"] {
+            cmd.options.make_mut()[0]
+                .names
+                .push(OptName::from_text(name).unwrap());
+        }
+
+        let out = NativeGenerator::generate_with_width(&cmd, Some(24));
+        let option: Vec<&str> = out
+            .lines()
+            .skip_while(|line| !line.starts_with("  --output"))
+            .collect();
+        assert!(option.len() > 1, "expected wrapped option: {:?}", out);
+        assert!(option[1..].iter().all(|line| line.starts_with("  ")));
+        for line in out.lines() {
+            assert!(line.chars().count() <= 24, "line too long: {:?}", line);
+        }
+
+        let unwrapped = NativeGenerator::generate(&cmd);
+        assert!(unwrapped.contains("  --output, -o, --out, --write-output-to (FILE)"));
     }
 }
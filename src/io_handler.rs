@@ -2,22 +2,96 @@ use anyhow::{Result, anyhow};
 use bstr::ByteSlice;
 use ecow::EcoString;
 use memchr::memchr;
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use tokio::io::AsyncReadExt;
 use tokio::process::Command as TokioCommand;
 
+// Matches pointers to a fuller help page, e.g. "See 'git help' for more" or
+// "Use --help for more information"
+static TERSE_HELP_HINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:see|run|use|try)\s+['"`]?[\w.-]*\s*(?:help|--help)['"`]?\s+for\s+more"#)
+        .unwrap()
+});
+
+/// The command passed to `--command` or `--subcommand` could not be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandNotFound(pub EcoString);
+
+impl std::fmt::Display for CommandNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "command not found: {} (is it installed and on your PATH?)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for CommandNotFound {}
+
+/// Environment that keeps help commands from starting an interactive pager
+const NO_PAGER_ENV: &[(&str, &str)] =
+    &[("PAGER", "cat"), ("MANPAGER", "cat"), ("GIT_PAGER", "cat")];
+
+/// Names that shells resolve before PATH, so `<command>-<name>` executables
+/// with these names are not treated as subcommands by `--exclude-builtin`
+pub const SHELL_BUILTINS: &[&str] = &[
+    "alias", "bg", "bind", "builtin", "cd", "command", "complete", "echo", "eval", "exec", "exit",
+    "export", "false", "fg", "help", "history", "jobs", "kill", "printf", "pwd", "read", "set",
+    "shift", "source", "test", "trap", "true", "type", "ulimit", "umask", "unalias", "unset",
+    "wait",
+];
+
+/// Line-level cleanup applied by [`IoHandler::normalize_text_with`] on top
+/// of tab expansion and space collapsing.
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizeOptions {
+    /// Remove whitespace at the end of every line
+    pub trim_trailing: bool,
+    /// Turn `\r\n` and lone `\r` line endings into `\n`
+    pub normalize_newlines: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            trim_trailing: true,
+            normalize_newlines: true,
+        }
+    }
+}
+
 pub struct IoHandler;
 
 impl IoHandler {
+    /// Path that stands for standard input, as in `--file -`
+    pub const STDIN_PATH: &str = "-";
+
+    /// Read a file, or all of standard input when `path` is `-`.
     pub async fn read_file(path: &str) -> Result<EcoString> {
+        if path == Self::STDIN_PATH {
+            let mut content = String::new();
+            tokio::io::stdin()
+                .read_to_string(&mut content)
+                .await
+                .map_err(|e| anyhow!("Failed to read standard input: {}", e))?;
+            return Ok(EcoString::from(content));
+        }
+
         let content = tokio::fs::read_to_string(path)
             .await
             .map_err(|e| anyhow!("Failed to read file {}: {}", path, e))?;
         Ok(EcoString::from(content))
     }
 
-    pub async fn read_from_command(cmd: &str) -> Result<EcoString> {
-        let output = TokioCommand::new("sh")
-            .arg("-c")
-            .arg(cmd)
+    /// Run `cmd` in `sh` and capture its output. With a `locale` (from
+    /// `--lang`), the command runs under it through `LC_ALL` and `LANG`, for
+    /// example `C` to get English section headers from tools that localize
+    /// their help; otherwise it inherits ours.
+    pub async fn read_from_command(cmd: &str, locale: Option<&str>) -> Result<EcoString> {
+        let output = Self::without_pager(TokioCommand::new("sh").arg("-c").arg(cmd), locale)
             .output()
             .await
             .map_err(|e| anyhow!("Failed to execute command: {}", e))?;
@@ -32,39 +106,202 @@ impl IoHandler {
     }
 
     pub async fn get_command_help(cmd: &str) -> Result<EcoString> {
-        Self::read_from_command(&format!("{} --help 2>/dev/null || {}", cmd, cmd)).await
+        Self::get_command_help_with_variant(cmd, None, None).await
+    }
+
+    /// Like `get_command_help`, but also runs `<cmd> <variant>` (for example
+    /// `--helpfull` or `--help=verbose`) and appends its output, so flags that
+    /// only appear in the verbose help are parsed too. Both run under
+    /// `locale`, as in [`IoHandler::read_from_command`].
+    pub async fn get_command_help_with_variant(
+        cmd: &str,
+        variant: Option<&str>,
+        locale: Option<&str>,
+    ) -> Result<EcoString> {
+        let help = Self::get_default_help(cmd, locale).await?;
+
+        let Some(variant) = variant else {
+            return Ok(help);
+        };
+
+        match Self::read_from_command(&format!("{} {} 2>/dev/null", cmd, variant), locale).await {
+            Ok(extra) if !extra.trim().is_empty() && extra != help => {
+                let mut merged = String::with_capacity(help.len() + extra.len() + 2);
+                merged.push_str(&help);
+                merged.push_str("\n\n");
+                merged.push_str(&extra);
+                Ok(EcoString::from(merged))
+            }
+            _ => Ok(help),
+        }
     }
 
-    pub async fn get_manpage(cmd: &str) -> Result<EcoString> {
-        Self::read_from_command(&format!("man {} 2>/dev/null | col -bx", cmd)).await
+    async fn get_default_help(cmd: &str, locale: Option<&str>) -> Result<EcoString> {
+        let help =
+            Self::read_from_command(&format!("{} --help 2>/dev/null || {}", cmd, cmd), locale)
+                .await?;
+
+        // Some tools print a short usage and point to `<cmd> help` for details
+        if Self::is_terse_help(&help)
+            && let Ok(full) =
+                Self::read_from_command(&format!("{} help 2>/dev/null", cmd), locale).await
+            && full.len() > help.len()
+        {
+            return Ok(full);
+        }
+
+        Ok(help)
+    }
+
+    /// Force pagers to `cat` and detach stdin, so a command that pages long
+    /// help (such as `git`) writes it straight to the captured output
+    /// instead of waiting on an interactive pager.
+    fn without_pager<'a>(
+        command: &'a mut TokioCommand,
+        locale: Option<&str>,
+    ) -> &'a mut TokioCommand {
+        if let Some(locale) = locale {
+            command.env("LC_ALL", locale).env("LANG", locale);
+        }
+        command
+            .envs(NO_PAGER_ENV.iter().copied())
+            .stdin(std::process::Stdio::null())
     }
 
+    /// Check whether `name` resolves to an executable, builtin or function
+    /// in `sh`. Only the first word is checked, so `git log` looks up `git`.
+    pub async fn command_exists(name: &str) -> bool {
+        let Some(program) = name.split_whitespace().next() else {
+            return false;
+        };
+
+        TokioCommand::new("sh")
+            .arg("-c")
+            .arg("command -v \"$1\" >/dev/null 2>&1")
+            .arg("sh")
+            .arg(program)
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Fail with `CommandNotFound` unless `name` exists.
+    pub async fn require_command(name: &str) -> Result<()> {
+        if Self::command_exists(name).await {
+            Ok(())
+        } else {
+            Err(CommandNotFound(EcoString::from(name)).into())
+        }
+    }
+
+    /// Find git-style external subcommands: executables named
+    /// `<base>-<name>` in the directories of `path` (or `$PATH`). Returns
+    /// the sorted, deduplicated `<name>` parts.
+    pub async fn path_subcommands(base: &str, path: Option<&std::ffi::OsStr>) -> Vec<EcoString> {
+        let path = match path {
+            Some(path) => path.to_os_string(),
+            None => std::env::var_os("PATH").unwrap_or_default(),
+        };
+        let prefix = format!("{}-", base);
+        let mut names = std::collections::BTreeSet::new();
+
+        for dir in std::env::split_paths(&path) {
+            let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let file_name = entry.file_name();
+                let Some(sub) = file_name
+                    .to_str()
+                    .and_then(|name| name.strip_prefix(&prefix))
+                else {
+                    continue;
+                };
+                if !sub.is_empty() && Self::is_executable(&entry).await {
+                    names.insert(EcoString::from(sub));
+                }
+            }
+        }
+
+        names.into_iter().collect()
+    }
+
+    /// Drop shell builtins and the base command itself from discovered
+    /// subcommand names.
+    pub fn exclude_builtins(base: &str, names: &mut Vec<EcoString>) {
+        names.retain(|name| name != base && !SHELL_BUILTINS.contains(&name.as_str()));
+    }
+
+    #[cfg(unix)]
+    async fn is_executable(entry: &tokio::fs::DirEntry) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::metadata(entry.path())
+            .await
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    async fn is_executable(entry: &tokio::fs::DirEntry) -> bool {
+        tokio::fs::metadata(entry.path())
+            .await
+            .is_ok_and(|meta| meta.is_file())
+    }
+
+    /// Pick the version number out of help or `--version` output that
+    /// opens with a line such as `git version 2.43.0`: the first word of the
+    /// first line that starts with a digit. Help that opens with its usage
+    /// line has no version.
+    pub fn parse_version(output: &str) -> Option<EcoString> {
+        let line = output.lines().find(|line| !line.trim().is_empty())?;
+        line.split_whitespace()
+            .map(|word| word.trim_start_matches('v'))
+            .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|word| EcoString::from(word.trim_end_matches([',', ';', ')'])))
+    }
+
+    /// Check whether help output only refers to a fuller help page.
+    pub fn is_terse_help(help: &str) -> bool {
+        TERSE_HELP_HINT.is_match(help)
+    }
+
+    pub async fn get_manpage(cmd: &str, locale: Option<&str>) -> Result<EcoString> {
+        Self::read_from_command(&format!("man {} 2>/dev/null | col -bx", cmd), locale).await
+    }
+
+    /// Normalize help text with the default [`NormalizeOptions`].
     pub fn normalize_text(text: &str) -> EcoString {
+        Self::normalize_text_with(text, NormalizeOptions::default())
+    }
+
+    /// Expand tabs to 8 spaces and collapse double spaces, then apply the
+    /// line-ending and trailing-whitespace policy in `options`.
+    pub fn normalize_text_with(text: &str, options: NormalizeOptions) -> EcoString {
         let bytes = text.as_bytes();
 
         // SIMD fast path: check if any tabs or double spaces exist
         let has_tabs = memchr(b'\t', bytes).is_some();
 
         // Quick check for double spaces - look for at least one space then another
-        let has_double_spaces = {
-            let mut found = false;
-            let mut iter = bytes.iter().peekable();
-            while let Some(&b) = iter.next() {
-                if b == b' '
-                    && let Some(&&next) = iter.peek()
-                    && next == b' '
-                {
-                    found = true;
-                    break;
-                }
-            }
-            found
-        };
+        let has_double_spaces = memchr::memmem::find(bytes, b"  ").is_some();
+        let has_cr = options.normalize_newlines && memchr(b'\r', bytes).is_some();
+        let has_trailing = options.trim_trailing
+            && (memchr::memmem::find(bytes, b" \n").is_some()
+                || memchr::memmem::find(bytes, b" \r").is_some()
+                || bytes.ends_with(b" "));
 
-        if !has_tabs && !has_double_spaces {
+        if !has_tabs && !has_double_spaces && !has_cr && !has_trailing {
             return EcoString::from(text);
         }
 
+        // `\r\n` is split by `lines()`; a lone `\r` (classic Mac) ends a line too
+        let unified;
+        let bytes = if has_cr {
+            unified = text.replace("\r\n", "\n").replace('\r', "\n");
+            unified.as_bytes()
+        } else {
+            bytes
+        };
+
         // Use bstr for SIMD-accelerated line iteration
         let mut result = String::with_capacity(text.len());
         let mut first = true;
@@ -79,22 +316,72 @@ impl IoHandler {
             let line_str = unsafe { std::str::from_utf8_unchecked(line) };
 
             // Apply transformations only if needed
-            if has_tabs && has_double_spaces {
-                let replaced = line_str.replace('\t', "        ").replace("  ", " ");
-                result.push_str(&replaced);
+            let line: Cow<'_, str> = if has_tabs && has_double_spaces {
+                Cow::Owned(line_str.replace('\t', "        ").replace("  ", " "))
             } else if has_tabs {
-                result.push_str(&line_str.replace('\t', "        "));
+                Cow::Owned(line_str.replace('\t', "        "))
+            } else if has_double_spaces {
+                Cow::Owned(line_str.replace("  ", " "))
+            } else {
+                Cow::Borrowed(line_str)
+            };
+            if options.trim_trailing {
+                result.push_str(line.trim_end());
             } else {
-                result.push_str(&line_str.replace("  ", " "));
+                result.push_str(&line);
             }
         }
 
         EcoString::from(result)
     }
 
+    /// Syntax-check a generated script with its shell's no-exec mode
+    /// (`bash -n`, `zsh -n`, `fish -n`, `elvish -compileonly`). Returns
+    /// `None` when the format has no checker or the shell is not installed.
+    pub async fn check_script_syntax(format: &str, script: &str) -> Option<Result<()>> {
+        use tokio::io::AsyncWriteExt;
+
+        let (shell, flag) = match format {
+            "bash" => ("bash", "-n"),
+            "zsh" => ("zsh", "-n"),
+            "fish" => ("fish", "-n"),
+            "elvish" => ("elvish", "-compileonly"),
+            _ => return None,
+        };
+
+        let mut child = match TokioCommand::new(shell)
+            .arg(flag)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        let check = async {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(script.as_bytes()).await?;
+            }
+            let output = child.wait_with_output().await?;
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "{} {} rejected the generated script:\n{}",
+                    shell,
+                    flag,
+                    String::from_utf8_lossy(&output.stderr).trim_end()
+                ))
+            }
+        };
+        Some(check.await)
+    }
+
     pub async fn is_man_available(cmd: &str) -> bool {
-        TokioCommand::new("man")
-            .arg(cmd)
+        Self::without_pager(TokioCommand::new("man").arg(cmd), None)
             .output()
             .await
             .map(|output| output.status.success())
@@ -106,6 +393,20 @@ impl IoHandler {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_version() {
+        let parse = |s| IoHandler::parse_version(s).map(|v| v.to_string());
+        assert_eq!(parse("git version 2.43.0\n").as_deref(), Some("2.43.0"));
+        assert_eq!(
+            parse("\nripgrep 14.1.0 (rev abc)").as_deref(),
+            Some("14.1.0")
+        );
+        assert_eq!(parse("tool v1.2.3, built today").as_deref(), Some("1.2.3"));
+        assert_eq!(parse("no version here"), None);
+        assert_eq!(parse("Usage: tool [OPTIONS]\nversion 1.0"), None);
+        assert_eq!(parse(""), None);
+    }
+
     #[test]
     fn test_normalize_text() {
         let input = "hello\t\tworld";
@@ -113,6 +414,36 @@ mod tests {
         assert!(!output.contains('\t'));
     }
 
+    #[test]
+    fn test_normalize_text_trailing_space_and_newlines() {
+        let input = "Usage: tool   \r\n  -v, --verbose    be verbose \r\n\rdone ";
+        assert_eq!(
+            IoHandler::normalize_text(input).as_str(),
+            "Usage: tool\n -v, --verbose  be verbose\n\ndone"
+        );
+
+        // Each policy can be turned off on its own
+        let keep_trailing = NormalizeOptions {
+            trim_trailing: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            IoHandler::normalize_text_with("a \r\nb", keep_trailing).as_str(),
+            "a \nb"
+        );
+        let keep_cr = NormalizeOptions {
+            normalize_newlines: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            IoHandler::normalize_text_with("a\rb", keep_cr).as_str(),
+            "a\rb"
+        );
+
+        // Clean input comes back unchanged
+        assert_eq!(IoHandler::normalize_text("a b\nc").as_str(), "a b\nc");
+    }
+
     #[tokio::test]
     async fn test_read_file() {
         use std::io::Write;
@@ -130,12 +461,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_read_from_command() {
-        let out = IoHandler::read_from_command("echo hello")
+        let out = IoHandler::read_from_command("echo hello", None)
             .await
             .expect("run echo");
         assert!(out.contains("hello"));
 
-        let res = IoHandler::read_from_command("exit 1").await;
+        let res = IoHandler::read_from_command("exit 1", None).await;
         assert!(res.is_err());
     }
 
@@ -145,6 +476,151 @@ mod tests {
         assert!(!help.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_command_exists() {
+        assert!(IoHandler::command_exists("sh").await);
+        assert!(IoHandler::command_exists("sh -c true").await);
+        assert!(!IoHandler::command_exists("d2o-no-such-command-xyz").await);
+        assert!(!IoHandler::command_exists("").await);
+
+        let err = IoHandler::require_command("d2o-no-such-command-xyz")
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<CommandNotFound>().is_some());
+    }
+
+    #[test]
+    fn test_is_terse_help() {
+        assert!(IoHandler::is_terse_help(
+            "Usage: git [OPTIONS]\nSee 'git help' for more information."
+        ));
+        assert!(IoHandler::is_terse_help(
+            "usage: foo\nUse --help for more information"
+        ));
+        assert!(!IoHandler::is_terse_help(
+            "Usage: foo\n  -v, --verbose  be verbose"
+        ));
+    }
+
+    /// Write an executable shell script standing in for a real command.
+    #[cfg(unix)]
+    fn fake_command(dir: &std::path::Path, name: &str, body: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).expect("write script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .expect("chmod script");
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_get_command_help_follows_terse_help() {
+        let dir = tempfile::TempDir::new().expect("create temp dir");
+        let script = fake_command(
+            dir.path(),
+            "fake",
+            r#"if [ "$1" = "help" ]; then
+  printf 'Usage: fake [OPTIONS]\n\nOptions:\n  -v, --verbose  Be verbose\n  -q, --quiet  Be quiet\n'
+else
+  printf "Usage: fake [OPTIONS]\nSee 'fake help' for more information.\n"
+fi"#,
+        );
+
+        let help = IoHandler::get_command_help(script.to_str().unwrap())
+            .await
+            .expect("get help");
+        let opts = crate::Layout::parse_blockwise(&help);
+        assert!(
+            opts.iter()
+                .any(|o| o.names.iter().any(|n| n.raw.as_str() == "--quiet"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_get_command_help_with_variant_merges_extra_flags() {
+        let dir = tempfile::TempDir::new().expect("create temp dir");
+        let script = fake_command(
+            dir.path(),
+            "gflags",
+            r#"if [ "$1" = "--helpfull" ]; then
+  printf 'Flags from main.cc:\n\n  --threads  Worker thread count\n\nFlags from logging.cc:\n\n  --logtostderr  Log to stderr\n'
+else
+  printf 'Usage: gflags [FLAGS]\n\n  --threads  Worker thread count\n\nTry --helpfull to get a list of all flags.\n'
+fi"#,
+        );
+        let script = script.to_str().unwrap();
+
+        let has_flag = |help: &str, flag: &str| {
+            crate::Layout::parse_blockwise(help)
+                .iter()
+                .any(|o| o.names.iter().any(|n| n.raw.as_str() == flag))
+        };
+
+        let help = IoHandler::get_command_help(script).await.expect("get help");
+        assert!(has_flag(&help, "--threads"));
+        assert!(!has_flag(&help, "--logtostderr"));
+
+        let full = IoHandler::get_command_help_with_variant(script, Some("--helpfull"), None)
+            .await
+            .expect("get verbose help");
+        assert!(has_flag(&full, "--threads"));
+        assert!(has_flag(&full, "--logtostderr"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_get_command_help_disables_pager() {
+        let dir = tempfile::TempDir::new().expect("create temp dir");
+        let script = fake_command(
+            dir.path(),
+            "paged",
+            r#"if [ "$PAGER" = "cat" ] && [ "$GIT_PAGER" = "cat" ] && [ ! -t 0 ]; then
+  printf 'Usage: paged [OPTIONS]\n\n  -v, --verbose  Be verbose\n'
+else
+  printf 'waiting on pager\n'
+fi"#,
+        );
+
+        let help = IoHandler::get_command_help(script.to_str().unwrap())
+            .await
+            .expect("get help");
+        assert!(help.contains("--verbose"), "got: {}", help);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_get_command_help_runs_under_locale() {
+        let dir = tempfile::TempDir::new().expect("create temp dir");
+        let script = fake_command(dir.path(), "localized", r#"printf 'locale=%s\n' "$LC_ALL""#);
+        let script = script.to_str().unwrap();
+
+        let help = IoHandler::get_command_help_with_variant(script, None, Some("C"))
+            .await
+            .expect("get help");
+        assert_eq!(help.trim(), "locale=C");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_path_subcommands_exclude_builtins() {
+        let dir = tempfile::TempDir::new().expect("create temp dir");
+        fake_command(dir.path(), "mytool-foo", "true");
+        fake_command(dir.path(), "mytool-help", "true");
+        fake_command(dir.path(), "mytool-mytool", "true");
+        fake_command(dir.path(), "othertool-bar", "true");
+        // Not executable, so not a subcommand
+        std::fs::write(dir.path().join("mytool-readme"), "").expect("write file");
+
+        let mut names = IoHandler::path_subcommands("mytool", Some(dir.path().as_os_str())).await;
+        assert_eq!(names, ["foo", "help", "mytool"]);
+
+        IoHandler::exclude_builtins("mytool", &mut names);
+        assert_eq!(names, ["foo"]);
+    }
+
     #[tokio::test]
     async fn test_is_man_available() {
         let _man_available = IoHandler::is_man_available("echo").await;
@@ -154,7 +630,9 @@ mod tests {
     #[tokio::test]
     async fn test_get_manpage() {
         if IoHandler::is_man_available("echo").await {
-            let man = IoHandler::get_manpage("echo").await.expect("get manpage");
+            let man = IoHandler::get_manpage("echo", None)
+                .await
+                .expect("get manpage");
             assert!(!man.is_empty());
         }
     }
@@ -1,49 +1,390 @@
-use crate::types::Command;
+use crate::generators::{GeneratorOptions, first_sentence};
+use crate::types::{Command, Opt};
+use anyhow::{Context, Result};
 use ecow::EcoString;
 use serde_json::json;
+use std::fmt::Write;
 
 pub struct JsonGenerator;
 
 impl JsonGenerator {
     pub fn generate(cmd: &Command) -> EcoString {
-        let json = Self::command_to_json(cmd);
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
+        let json = Self::command_to_json(cmd, options);
         EcoString::from(serde_json::to_string_pretty(&json).unwrap_or_default())
     }
 
-    fn command_to_json(cmd: &Command) -> serde_json::Value {
+    fn command_to_json(cmd: &Command, options: &GeneratorOptions) -> serde_json::Value {
+        let (opts, subcommands) = ordered_children(cmd, options);
+
         let mut obj = json!({
             "name": cmd.name.as_str(),
             "description": cmd.description.as_str(),
             "usage": cmd.usage.as_str(),
-            "options": cmd.options.iter().map(|opt| {
-                json!({
-                    "names": opt.names.iter().map(|n| n.raw.as_str()).collect::<Vec<_>>(),
+            "options": opts.iter().map(|opt| {
+                let mut obj = json!({
+                    "names": Self::names_to_json(opt, options),
                     "argument": opt.argument.as_str(),
                     "description": opt.description.as_str(),
-                })
+                    // The first-sentence form shells display, for building tooltips
+                    "short_description": first_sentence(&opt.description).trim_end(),
+                });
+                if opt.multiple {
+                    obj["multiple"] = json!(true);
+                }
+                if opt.toggle {
+                    obj["toggle"] = json!(true);
+                }
+                if opt.optional_argument {
+                    obj["optional_argument"] = json!(true);
+                }
+                if let Some(range) = &opt.value_range {
+                    obj["value_range"] = json!(range);
+                }
+                obj
             }).collect::<Vec<_>>(),
         });
 
-        if !cmd.subcommands.is_empty() {
+        if !cmd.positionals.is_empty() {
+            obj["positionals"] = json!(cmd.positionals);
+        }
+
+        if !cmd.subcommands.is_empty() || options.json_include_empty_sections {
             obj["subcommands"] = serde_json::json!(
-                cmd.subcommands
+                subcommands
                     .iter()
-                    .map(|sub| {
-                        json!({
-                            "name": sub.name.as_str(),
-                            "description": sub.description.as_str(),
-                        })
-                    })
+                    .map(|sub| Self::command_to_json(sub, options))
                     .collect::<Vec<_>>()
             );
         }
 
-        if !cmd.version.is_empty() {
+        if !cmd.version.is_empty() || options.json_include_empty_sections {
             obj["version"] = json!(cmd.version.as_str());
         }
 
+        if let Some(metadata) = &cmd.metadata {
+            obj["metadata"] = metadata.clone();
+        }
+
         obj
     }
+
+    /// Plain strings by default; `{raw, type}` objects when typed output is requested.
+    fn names_to_json(opt: &Opt, options: &GeneratorOptions) -> serde_json::Value {
+        if options.json_typed {
+            json!(opt.names)
+        } else {
+            json!(opt.names.iter().map(|n| n.raw.as_str()).collect::<Vec<_>>())
+        }
+    }
+}
+
+/// Hand-editable JSON5 output in d2o's Command schema, with comments, unquoted
+/// keys and trailing commas. Subcommands are written in full so the file
+/// round-trips through `--loadjson5`.
+pub struct Json5Generator;
+
+impl Json5Generator {
+    pub fn generate(cmd: &Command) -> EcoString {
+        Self::generate_with_options(cmd, &GeneratorOptions::default())
+    }
+
+    pub fn generate_with_options(cmd: &Command, options: &GeneratorOptions) -> EcoString {
+        let mut buf = String::with_capacity(256 + cmd.options.len() * 128);
+        let _ = writeln!(
+            buf,
+            "// Completion definition for {}, load it back with --loadjson5",
+            cmd.name
+        );
+        Self::write_command(&mut buf, cmd, options, "");
+        buf.push('\n');
+        EcoString::from(buf)
+    }
+
+    /// Parse JSON5 text in the Command schema.
+    pub fn parse(text: &str) -> Result<Command> {
+        let json = Self::to_json(&Self::strip_comments(text));
+        serde_json::from_str(&json).context("Invalid JSON5 Command definition")
+    }
+
+    fn write_command(buf: &mut String, cmd: &Command, options: &GeneratorOptions, pad: &str) {
+        let _ = writeln!(buf, "{{");
+        let _ = writeln!(buf, "{}  name: {},", pad, quote(&cmd.name));
+        let _ = writeln!(buf, "{}  description: {},", pad, quote(&cmd.description));
+        let _ = writeln!(buf, "{}  usage: {},", pad, quote(&cmd.usage));
+
+        let (opts, subcommands) = ordered_children(cmd, options);
+        let _ = writeln!(buf, "{}  options: [", pad);
+        for opt in opts {
+            let names = opt
+                .names
+                .iter()
+                .map(|name| {
+                    if options.json_typed {
+                        format!(
+                            "{{ raw: {}, type: {} }}",
+                            quote(&name.raw),
+                            json!(name.opt_type)
+                        )
+                    } else {
+                        quote(&name.raw)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let _ = writeln!(buf, "{}    {{", pad);
+            let _ = writeln!(buf, "{}      names: [{}],", pad, names);
+            let _ = writeln!(buf, "{}      argument: {},", pad, quote(&opt.argument));
+            let _ = writeln!(
+                buf,
+                "{}      description: {},",
+                pad,
+                quote(&opt.description)
+            );
+            if opt.multiple {
+                let _ = writeln!(buf, "{}      multiple: true,", pad);
+            }
+            if opt.toggle {
+                let _ = writeln!(buf, "{}      toggle: true,", pad);
+            }
+            if opt.optional_argument {
+                let _ = writeln!(buf, "{}      optional_argument: true,", pad);
+            }
+            if let Some((low, high)) = &opt.value_range {
+                let _ = writeln!(
+                    buf,
+                    "{}      value_range: [{}, {}],",
+                    pad,
+                    quote(low),
+                    quote(high)
+                );
+            }
+            let _ = writeln!(buf, "{}    }},", pad);
+        }
+        let _ = writeln!(buf, "{}  ],", pad);
+
+        if !cmd.positionals.is_empty() {
+            let _ = writeln!(buf, "{}  positionals: [", pad);
+            for positional in cmd.positionals.iter() {
+                let _ = writeln!(
+                    buf,
+                    "{}    {{ name: {}, description: {} }},",
+                    pad,
+                    quote(&positional.name),
+                    quote(&positional.description)
+                );
+            }
+            let _ = writeln!(buf, "{}  ],", pad);
+        }
+
+        if !subcommands.is_empty() || options.json_include_empty_sections {
+            let sub_pad = format!("{}    ", pad);
+            let _ = writeln!(buf, "{}  subcommands: [", pad);
+            for sub in subcommands {
+                let _ = writeln!(buf, "{}// {} {}", sub_pad, cmd.name, sub.name);
+                let _ = write!(buf, "{}", sub_pad);
+                Self::write_command(buf, sub, options, &sub_pad);
+                let _ = writeln!(buf, ",");
+            }
+            let _ = writeln!(buf, "{}  ],", pad);
+        }
+
+        if !cmd.version.is_empty() || options.json_include_empty_sections {
+            let _ = writeln!(buf, "{}  version: {},", pad, quote(&cmd.version));
+        }
+
+        if let Some(metadata) = &cmd.metadata {
+            let _ = writeln!(buf, "{}  metadata: {},", pad, metadata);
+        }
+
+        let _ = write!(buf, "{}}}", pad);
+    }
+
+    /// Remove `//` and `/* */` comments outside of strings.
+    fn strip_comments(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        let mut quote_char = None;
+
+        while let Some(c) = chars.next() {
+            match quote_char {
+                Some(q) => {
+                    out.push(c);
+                    if c == '\\' {
+                        out.extend(chars.next());
+                    } else if c == q {
+                        quote_char = None;
+                    }
+                }
+                None if c == '/' && chars.peek() == Some(&'/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            out.push('\n');
+                            break;
+                        }
+                    }
+                }
+                None if c == '/' && chars.peek() == Some(&'*') => {
+                    chars.next();
+                    let mut prev = '\0';
+                    for c in chars.by_ref() {
+                        if prev == '*' && c == '/' {
+                            break;
+                        }
+                        prev = c;
+                    }
+                    out.push(' ');
+                }
+                None => {
+                    if c == '"' || c == '\'' {
+                        quote_char = Some(c);
+                    }
+                    out.push(c);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Rewrite comment-free JSON5 as JSON: quote identifier keys, turn
+    /// single-quoted strings into double-quoted ones, join line
+    /// continuations, drop trailing commas and rewrite JSON5-only numbers.
+    /// JSON has no `Infinity` or `NaN`, so those load as `null`.
+    fn to_json(text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len() + text.len() / 4);
+        let next_significant = |from: usize| chars[from..].iter().find(|c| !c.is_whitespace());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                '"' | '\'' => {
+                    out.push('"');
+                    i += 1;
+                    while i < chars.len() && chars[i] != c {
+                        match chars[i] {
+                            // A backslash before a line break continues the string
+                            '\\' if matches!(chars.get(i + 1), Some('\n' | '\r')) => {
+                                i += 2;
+                                if chars[i - 1] == '\r' && chars.get(i) == Some(&'\n') {
+                                    i += 1;
+                                }
+                                continue;
+                            }
+                            '\\' if i + 1 < chars.len() => {
+                                // `\'` needs no escape inside double quotes
+                                if chars[i + 1] != '\'' {
+                                    out.push('\\');
+                                }
+                                out.push(chars[i + 1]);
+                                i += 2;
+                                continue;
+                            }
+                            '"' => out.push_str("\\\""),
+                            other => out.push(other),
+                        }
+                        i += 1;
+                    }
+                    out.push('"');
+                }
+                ',' if matches!(next_significant(i + 1), Some('}' | ']')) => {}
+                // JSON has no leading `+`; `-Infinity` and `-NaN` become `null`
+                '+' | '-' if matches!(chars.get(i + 1), Some(c) if c.is_ascii_digit() || matches!(c, '.' | 'I' | 'N')) => {
+                    if c == '-' && !matches!(chars[i + 1], 'I' | 'N') {
+                        out.push('-');
+                    }
+                }
+                c if c.is_ascii_digit()
+                    || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) =>
+                {
+                    let start = i;
+                    while i + 1 < chars.len()
+                        && (chars[i + 1].is_ascii_alphanumeric()
+                            || chars[i + 1] == '.'
+                            || (matches!(chars[i + 1], '+' | '-')
+                                && matches!(chars[i], 'e' | 'E')
+                                && !matches!(chars.get(start + 1), Some('x' | 'X'))))
+                    {
+                        i += 1;
+                    }
+                    let number: String = chars[start..=i].iter().collect();
+                    Self::push_number(&mut out, &number);
+                }
+                c if c.is_ascii_alphabetic() || c == '_' || c == '$' => {
+                    let start = i;
+                    while i + 1 < chars.len()
+                        && (chars[i + 1].is_ascii_alphanumeric()
+                            || matches!(chars[i + 1], '_' | '$'))
+                    {
+                        i += 1;
+                    }
+                    let ident: String = chars[start..=i].iter().collect();
+                    if next_significant(i + 1) == Some(&':') {
+                        out.push('"');
+                        out.push_str(&ident);
+                        out.push('"');
+                    } else if ident == "Infinity" || ident == "NaN" {
+                        out.push_str("null");
+                    } else {
+                        out.push_str(&ident);
+                    }
+                }
+                c => out.push(c),
+            }
+            i += 1;
+        }
+
+        out
+    }
+
+    /// Write a JSON5 number as JSON: hex becomes decimal, and a bare
+    /// leading or trailing decimal point gets its zero.
+    fn push_number(out: &mut String, number: &str) {
+        if let Some(hex) = number
+            .strip_prefix("0x")
+            .or_else(|| number.strip_prefix("0X"))
+            && let Ok(value) = u64::from_str_radix(hex, 16)
+        {
+            out.push_str(&value.to_string());
+            return;
+        }
+        if number.starts_with('.') {
+            out.push('0');
+        }
+        match number.find('.') {
+            Some(dot) if !number[dot + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                out.push_str(&number[..=dot]);
+                out.push('0');
+                out.push_str(&number[dot + 1..]);
+            }
+            _ => out.push_str(number),
+        }
+    }
+}
+
+/// `cmd`'s options and subcommands, sorted by name with `json_sorted`.
+fn ordered_children<'a>(
+    cmd: &'a Command,
+    options: &GeneratorOptions,
+) -> (Vec<&'a Opt>, Vec<&'a Command>) {
+    let mut opts: Vec<&Opt> = cmd.options.iter().collect();
+    let mut subcommands: Vec<&Command> = cmd.subcommands.iter().collect();
+    if options.json_sorted {
+        opts.sort_by(|a, b| (a.canonical_name(), &a.names).cmp(&(b.canonical_name(), &b.names)));
+        subcommands.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    (opts, subcommands)
+}
+
+/// Quote a string as a JSON (and therefore JSON5) string literal.
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -58,6 +399,7 @@ mod tests {
             description: EcoString::from("Test command"),
             usage: EcoString::from("test [OPTIONS]"),
             options: EcoVec::new(),
+            positionals: EcoVec::new(),
             subcommands: {
                 let mut v = EcoVec::new();
                 v.push(Command {
@@ -65,12 +407,15 @@ mod tests {
                     description: EcoString::from("Subcommand"),
                     usage: EcoString::new(),
                     options: EcoVec::new(),
+                    positionals: EcoVec::new(),
                     subcommands: EcoVec::new(),
                     version: EcoString::new(),
+                    metadata: None,
                 });
                 v
             },
             version: EcoString::from("1.0.0"),
+            metadata: None,
         };
 
         let json_str = JsonGenerator::generate(&cmd);
@@ -106,11 +451,17 @@ mod tests {
                     },
                     argument: EcoString::from("FILE"),
                     description: EcoString::from("Enable verbose mode"),
+                    multiple: false,
+                    toggle: false,
+                    optional_argument: false,
+                    value_range: None,
                 });
                 v
             },
+            positionals: EcoVec::new(),
             subcommands: EcoVec::new(),
             version: EcoString::new(),
+            metadata: None,
         };
 
         let json_str = JsonGenerator::generate(&cmd);
@@ -122,4 +473,264 @@ mod tests {
         assert_eq!(opt["argument"], "FILE");
         assert_eq!(opt["description"], "Enable verbose mode");
     }
+
+    #[test]
+    fn test_json_generator_typed_names_roundtrip() {
+        let mut cmd = Command::new(EcoString::from("xclock"));
+        cmd.options.push(crate::types::Opt {
+            names: {
+                let mut names = EcoVec::new();
+                names.push(crate::types::OptName::new(
+                    EcoString::from("-display"),
+                    crate::types::OptNameType::OldType,
+                ));
+                names
+            },
+            argument: EcoString::from("DISPLAY"),
+            description: EcoString::from("X server to contact"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        let options = GeneratorOptions {
+            json_typed: true,
+            ..Default::default()
+        };
+        let json_str = JsonGenerator::generate_with_options(&cmd, &options);
+        let value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["options"][0]["names"][0]["raw"], "-display");
+        assert_eq!(value["options"][0]["names"][0]["type"], "OLDTYPE");
+
+        let loaded: Command = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(loaded.options[0].names, cmd.options[0].names);
+    }
+
+    #[test]
+    fn test_json_generator_opt_flags_roundtrip() {
+        let mut cmd = Command::new(EcoString::from("tool"));
+        let mut names = EcoVec::new();
+        names.push(crate::types::OptName::from_text("--color").unwrap());
+        cmd.options.push(Opt {
+            names,
+            argument: EcoString::from("WHEN"),
+            description: EcoString::from("Colorize output"),
+            multiple: false,
+            toggle: false,
+            optional_argument: true,
+            value_range: None,
+        });
+        let mut names = EcoVec::new();
+        names.push(crate::types::OptName::from_text("--include").unwrap());
+        cmd.options.push(Opt {
+            names,
+            argument: EcoString::from("DIR"),
+            description: EcoString::from("Add a search directory"),
+            multiple: true,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        let mut names = EcoVec::new();
+        names.push(crate::types::OptName::from_text("+x").unwrap());
+        names.push(crate::types::OptName::from_text("-x").unwrap());
+        cmd.options.push(Opt {
+            names,
+            argument: EcoString::new(),
+            description: EcoString::from("Trace commands"),
+            multiple: false,
+            toggle: true,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        let json_str = JsonGenerator::generate(&cmd);
+        let value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["options"][0]["optional_argument"], true);
+        assert_eq!(value["options"][2]["toggle"], true);
+        assert!(value["options"][0].get("multiple").is_none());
+        assert_eq!(value["options"][1]["multiple"], true);
+
+        let loaded: Command = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(loaded, cmd);
+    }
+
+    #[test]
+    fn test_positionals_roundtrip() {
+        let mut cmd = Command::new(EcoString::from("tool"));
+        cmd.positionals.push(crate::types::Positional {
+            name: EcoString::from("<FILE>"),
+            description: EcoString::from("The input file"),
+        });
+
+        let json: Command = serde_json::from_str(&JsonGenerator::generate(&cmd)).unwrap();
+        assert_eq!(json.positionals, cmd.positionals);
+        let json5 = Json5Generator::parse(&Json5Generator::generate(&cmd)).unwrap();
+        assert_eq!(json5.positionals, cmd.positionals);
+
+        let without = JsonGenerator::generate(&Command::new(EcoString::from("tool")));
+        assert!(!without.contains("positionals"));
+    }
+
+    #[test]
+    fn test_json_generator_include_empty_sections() {
+        let cmd = Command::new(EcoString::from("test"));
+
+        let json_str = JsonGenerator::generate(&cmd);
+        let value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert!(value.get("subcommands").is_none());
+        assert!(value.get("version").is_none());
+
+        let options = GeneratorOptions {
+            json_include_empty_sections: true,
+            ..Default::default()
+        };
+        let json_str = JsonGenerator::generate_with_options(&cmd, &options);
+        let value: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(value["subcommands"], serde_json::json!([]));
+        assert_eq!(value["version"], "");
+    }
+
+    #[test]
+    fn test_json_generator_short_description() {
+        let mut cmd = Command::new(EcoString::from("test"));
+        let mut names = EcoVec::new();
+        names.push(crate::types::OptName::from_text("--out").unwrap());
+        cmd.options.push(Opt {
+            names,
+            argument: EcoString::from("FILE"),
+            description: EcoString::from("Write to FILE. Existing files are replaced."),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        let value: serde_json::Value =
+            serde_json::from_str(&JsonGenerator::generate(&cmd)).unwrap();
+        let opt = &value["options"][0];
+        assert_eq!(
+            opt["description"],
+            "Write to FILE. Existing files are replaced."
+        );
+        assert_eq!(opt["short_description"], "Write to FILE");
+
+        // Consumers reading the JSON back ignore the derived field
+        let loaded: Command = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded, cmd);
+    }
+
+    #[test]
+    fn test_json5_roundtrip_with_comments() {
+        let text = r#"
+// Hand-edited definition
+{
+  name: 'tool',
+  description: "A tool", /* inline comment */
+  usage: 'tool [OPTIONS]',
+  options: [
+    {
+      names: ['-v', "--verbose"],
+      argument: '',
+      description: 'Don\'t be quiet // not a comment',
+    },
+  ],
+  subcommands: [
+    {
+      name: "run",
+      description: "Run it",
+      usage: "",
+      options: [{ names: ["-n"], argument: "N", description: "Count", multiple: true }],
+    },
+  ],
+}
+"#;
+
+        let cmd = Json5Generator::parse(text).expect("parse JSON5");
+        assert_eq!(cmd.name.as_str(), "tool");
+        assert_eq!(cmd.options[0].names[1].raw.as_str(), "--verbose");
+        assert_eq!(
+            cmd.options[0].description.as_str(),
+            "Don't be quiet // not a comment"
+        );
+        assert_eq!(cmd.subcommands[0].options[0].argument.as_str(), "N");
+        assert!(cmd.subcommands[0].options[0].multiple);
+
+        let emitted = Json5Generator::generate(&cmd);
+        assert!(emitted.starts_with("// Completion definition for tool"));
+        assert!(emitted.contains("    // tool run\n"));
+        let reloaded = Json5Generator::parse(&emitted).expect("parse emitted JSON5");
+        assert_eq!(reloaded, cmd);
+
+        let typed = GeneratorOptions {
+            json_typed: true,
+            ..Default::default()
+        };
+        let emitted = Json5Generator::generate_with_options(&cmd, &typed);
+        assert!(emitted.contains("{ raw: \"-v\", type: \"SHORTTYPE\" }"));
+        assert_eq!(Json5Generator::parse(&emitted).unwrap(), cmd);
+    }
+
+    #[test]
+    fn test_json5_parse_strings_and_numbers() {
+        let text = "{
+  name: 'tool',
+  description: 'A long \\
+description',
+  usage: \"tool \\\r\n[OPTIONS]\",
+  options: [],
+  metadata: { hex: 0x1F, plus: +3, lead: .5, trail: 2., exp: +1e+2, inf: Infinity, neg: -Infinity, nan: NaN, minus: -0xA },
+}";
+
+        let cmd = Json5Generator::parse(text).expect("parse JSON5");
+        assert_eq!(cmd.description.as_str(), "A long description");
+        assert_eq!(cmd.usage.as_str(), "tool [OPTIONS]");
+        assert_eq!(
+            cmd.metadata,
+            Some(serde_json::json!({
+                "hex": 31, "plus": 3, "lead": 0.5, "trail": 2.0, "exp": 100.0,
+                "inf": null, "neg": null, "nan": null, "minus": -10,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_json5_generator_honors_sorting_and_empty_sections() {
+        let mut cmd = Command::new(EcoString::from("tool"));
+        for name in ["--zeta", "--alpha"] {
+            let mut names = EcoVec::new();
+            names.push(crate::types::OptName::from_text(name).unwrap());
+            cmd.options.push(Opt {
+                names,
+                argument: EcoString::new(),
+                description: EcoString::new(),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
+            });
+        }
+
+        let plain = Json5Generator::generate(&cmd);
+        assert!(plain.find("--zeta").unwrap() < plain.find("--alpha").unwrap());
+        assert!(!plain.contains("subcommands"));
+        assert!(!plain.contains("version"));
+
+        let options = GeneratorOptions {
+            json_sorted: true,
+            json_include_empty_sections: true,
+            ..Default::default()
+        };
+        let emitted = Json5Generator::generate_with_options(&cmd, &options);
+        assert!(emitted.find("--alpha").unwrap() < emitted.find("--zeta").unwrap());
+        assert!(emitted.contains("subcommands: ["));
+        assert!(emitted.contains("version: \"\","));
+        let json = JsonGenerator::generate_with_options(&cmd, &options);
+        assert_eq!(
+            Json5Generator::parse(&emitted).unwrap(),
+            serde_json::from_str::<Command>(&json).unwrap()
+        );
+    }
 }
@@ -1,40 +1,387 @@
 use crate::parser::Parser;
-use crate::types::Opt;
+use crate::types::{Opt, OptName, Positional};
 use bstr::ByteSlice;
 use ecow::{EcoString, EcoVec};
-use memchr::memchr;
+use memchr::{memchr, memchr3};
 use rayon::prelude::*;
+use std::borrow::Cow;
 
 pub struct Layout;
 
 impl Layout {
+    /// Sections holding sample invocations or references rather than options
+    const SKIPPED_SECTIONS: &[&str] = &["example", "examples", "see also"];
+
+    /// Drop `Examples:`/`See also:` sections, whose sample command lines
+    /// (`cmd -v file`) would otherwise be read as options. Only headings at
+    /// section level count; one nested deeper than the last option line is
+    /// part of that option's description. A section runs until the next
+    /// heading no deeper than its body, or an option line no deeper than the
+    /// heading itself.
+    pub fn strip_skipped_sections(content: &str) -> Cow<'_, str> {
+        let lines = Self::split_lines(content);
+        if !lines
+            .iter()
+            .any(|line| Self::is_skipped_heading(line.trim()))
+        {
+            return Cow::Borrowed(content);
+        }
+
+        let mut kept = String::with_capacity(content.len());
+        // (heading indent, body indent once seen)
+        let mut skip: Option<(usize, Option<usize>)> = None;
+        let mut option_indent: Option<usize> = None;
+        for line in lines {
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+            if let Some((section_indent, body_indent)) = &mut skip {
+                let ends = if trimmed.starts_with('-') {
+                    indent <= *section_indent
+                } else {
+                    Self::heading_name(trimmed).is_some()
+                        && body_indent.is_none_or(|body| indent <= body)
+                };
+                if !ends {
+                    if body_indent.is_none() && !trimmed.is_empty() {
+                        *body_indent = Some(indent);
+                    }
+                    continue;
+                }
+                skip = None;
+            }
+            if Self::is_skipped_heading(trimmed) && option_indent.is_none_or(|opt| indent <= opt) {
+                skip = Some((indent, None));
+                continue;
+            }
+            if trimmed.starts_with('-') {
+                option_indent = Some(indent);
+            }
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        Cow::Owned(kept)
+    }
+
+    fn is_skipped_heading(trimmed: &str) -> bool {
+        Self::heading_name(trimmed)
+            .is_some_and(|name| Self::SKIPPED_SECTIONS.contains(&name.as_str()))
+    }
+
+    /// The lowercased name of a section heading such as `Examples:` or a man
+    /// page's `SEE ALSO`.
+    fn heading_name(trimmed: &str) -> Option<String> {
+        if trimmed.starts_with('-') {
+            return None;
+        }
+        let name = match trimmed.strip_suffix(':') {
+            Some(name) => name,
+            None if trimmed.chars().any(char::is_alphabetic)
+                && !trimmed.chars().any(char::is_lowercase) =>
+            {
+                trimmed
+            }
+            None => return None,
+        };
+        (!name.is_empty() && name.split_whitespace().count() <= 3).then(|| name.to_lowercase())
+    }
+
+    /// Split text into lines using bstr's SIMD-accelerated line iteration.
+    /// Pure-ASCII input (the common case) is checked once up front so the
+    /// per-line UTF-8 validation can be skipped.
+    pub(crate) fn split_lines(content: &str) -> Vec<&str> {
+        let bytes = content.as_bytes();
+        if content.is_ascii() {
+            bytes
+                .lines()
+                // Safe: every byte of ASCII text is a char boundary
+                .map(|line| unsafe { std::str::from_utf8_unchecked(line) })
+                .collect()
+        } else {
+            bytes
+                .lines()
+                .filter_map(|line| std::str::from_utf8(line).ok())
+                .collect()
+        }
+    }
+
+    /// Fence info strings whose blocks hold help or usage text
+    const HELP_FENCE_LANGUAGES: &[&str] = &["", "text", "txt", "console", "sh", "shell"];
+
+    /// Concatenate the fenced `text`, `console` and `sh` code blocks of a
+    /// Markdown document, dropping the prose and other languages.
+    pub fn extract_fenced_blocks(markdown: &str) -> EcoString {
+        let mut out = String::new();
+        // Fence marker and whether the open block is kept
+        let mut open: Option<(&str, bool)> = None;
+
+        for line in Self::split_lines(markdown) {
+            let trimmed = line.trim_start();
+            let marker_len = trimmed
+                .bytes()
+                .take_while(|&b| b == b'`' || b == b'~')
+                .count();
+            let marker = &trimmed[..marker_len];
+            let is_fence = marker_len >= 3 && marker.bytes().all(|b| b == marker.as_bytes()[0]);
+
+            match open {
+                None if is_fence => {
+                    let language = trimmed[marker_len..].split_whitespace().next();
+                    let keep = Self::HELP_FENCE_LANGUAGES
+                        .iter()
+                        .any(|l| l.eq_ignore_ascii_case(language.unwrap_or_default()));
+                    open = Some((marker, keep));
+                }
+                None => {}
+                Some((opening, keep)) => {
+                    if is_fence
+                        && marker.starts_with(opening)
+                        && trimmed[marker_len..].trim().is_empty()
+                    {
+                        if keep {
+                            // Keep blocks apart so they parse independently
+                            out.push('\n');
+                        }
+                        open = None;
+                    } else if keep {
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+
+        EcoString::from(out)
+    }
+
+    /// Sections listing positional arguments
+    const POSITIONAL_SECTIONS: &[&str] = &["args", "arguments", "positional arguments"];
+
+    /// Parse the entries of `ARGS:`, `Arguments:` and `positional
+    /// arguments:` sections, such as `<FILE>  the input file`. A description
+    /// may sit after a column gap or on the following, deeper indented lines.
+    pub fn parse_positionals(content: &str) -> EcoVec<Positional> {
+        let mut positionals = EcoVec::new();
+        // Indent of the open positional section's heading
+        let mut section: Option<usize> = None;
+        let mut current: Option<(usize, Positional)> = None;
+
+        for line in Self::split_lines(content) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let indent = line.len() - line.trim_start().len();
+
+            // Entries such as `FILE` look like headings, so only a line no
+            // deeper than the section's own heading can end it
+            if section.is_none_or(|heading| indent <= heading)
+                && let Some(name) = Self::heading_name(trimmed)
+            {
+                positionals.extend(current.take().map(|(_, p)| p));
+                section = Self::POSITIONAL_SECTIONS
+                    .contains(&name.as_str())
+                    .then_some(indent);
+                continue;
+            }
+            if section.is_none() {
+                continue;
+            }
+
+            if let Some((entry, positional)) = current.as_mut()
+                && indent > *entry
+            {
+                for word in trimmed.split_whitespace() {
+                    if !positional.description.is_empty() {
+                        positional.description.push(' ');
+                    }
+                    positional.description.push_str(word);
+                }
+                continue;
+            }
+
+            if trimmed.starts_with('-') {
+                positionals.extend(current.take().map(|(_, p)| p));
+                continue;
+            }
+            let (name, desc) = match trimmed.find("  ") {
+                Some(gap) => (&trimmed[..gap], trimmed[gap..].trim_start()),
+                None => trimmed.split_once(' ').unwrap_or((trimmed, "")),
+            };
+            // Prose such as `The following arguments are accepted:` continues
+            // the open entry, if any
+            if !Self::is_placeholder(name) {
+                if let Some((_, positional)) = current.as_mut() {
+                    for word in trimmed.split_whitespace() {
+                        positional.description.push(' ');
+                        positional.description.push_str(word);
+                    }
+                }
+                continue;
+            }
+            positionals.extend(current.take().map(|(_, p)| p));
+            let description =
+                EcoString::from(desc.split_whitespace().collect::<Vec<_>>().join(" "));
+            current = Some((
+                indent,
+                Positional {
+                    name: EcoString::from(name),
+                    description,
+                },
+            ));
+        }
+        positionals.extend(current.map(|(_, p)| p));
+
+        positionals
+    }
+
+    /// Whether `name` is shaped like an argument placeholder: bracketed
+    /// (`<FILE>`, `[DIR]`, `{start,stop}`) or ALL-CAPS (`FILE`), either
+    /// optionally followed by `...`.
+    fn is_placeholder(name: &str) -> bool {
+        let base = name.strip_suffix("...").unwrap_or(name);
+        let bracketed = [('<', '>'), ('[', ']'), ('{', '}')]
+            .iter()
+            .any(|&(open, close)| {
+                base.len() > 2 && base.starts_with(open) && base.ends_with(close)
+            });
+        bracketed
+            || (base.chars().any(|c| c.is_ascii_uppercase())
+                && base.chars().all(|c| {
+                    c.is_ascii_uppercase() || c.is_ascii_digit() || matches!(c, '_' | '-')
+                }))
+    }
+
+    /// Headers that only introduce a list and say nothing about its options
+    const GENERIC_GROUP_HEADERS: &[&str] = &[
+        "arguments",
+        "commands",
+        "flags",
+        "global flags",
+        "global options",
+        "optional arguments",
+        "options",
+        "positional arguments",
+        "usage",
+    ];
+
+    /// Map each option name to the group header it is listed under, e.g.
+    /// `--proxy` below `Network:` maps to `Network`. Generic headers such as
+    /// `Options:` are skipped.
+    pub fn group_headers(content: &str) -> std::collections::HashMap<EcoString, EcoString> {
+        let mut groups = std::collections::HashMap::new();
+        let mut current: Option<&str> = None;
+
+        for line in Self::split_lines(content) {
+            let trimmed = line.trim();
+            if trimmed.starts_with('-') {
+                if let Some(header) = current {
+                    for name in trimmed
+                        .split_whitespace()
+                        .take_while(|word| word.starts_with('-'))
+                    {
+                        let name = name.trim_end_matches(',');
+                        let name = name.split_once(['=', '[']).map_or(name, |(n, _)| n);
+                        groups.insert(EcoString::from(name), EcoString::from(header));
+                    }
+                }
+            } else if let Some(header) = trimmed.strip_suffix(':')
+                && !header.is_empty()
+                && header.len() <= 40
+                && !header.contains(':')
+            {
+                let generic = Self::GENERIC_GROUP_HEADERS
+                    .iter()
+                    .any(|g| header.eq_ignore_ascii_case(g));
+                current = (!generic).then_some(header);
+            }
+        }
+
+        groups
+    }
+
+    /// Use the group header as the description of options that have none.
+    pub fn apply_group_descriptions(options: &mut EcoVec<Opt>, content: &str) {
+        if options.iter().all(|opt| !opt.description.is_empty()) {
+            return;
+        }
+
+        let groups = Self::group_headers(content);
+        for opt in options.make_mut() {
+            if !opt.description.is_empty() {
+                continue;
+            }
+            if let Some(header) = opt.names.iter().find_map(|name| groups.get(&name.raw)) {
+                opt.description = header.clone();
+            }
+        }
+    }
+
     /// Parse content into options, processing blocks in parallel.
+    ///
+    /// Help that lists its options twice (a short summary, then a detailed
+    /// section) yields each option once, with the richer description.
     pub fn parse_blockwise(content: &str) -> EcoVec<Opt> {
-        let blocks = Self::split_into_blocks_fast(content);
+        Self::parse_blockwise_with(content, false)
+    }
+
+    /// Like [`Layout::parse_blockwise`], also reading DOS/Windows-style
+    /// `/flag` lines as options when `slash_flags` is set.
+    pub fn parse_blockwise_with(content: &str, slash_flags: bool) -> EcoVec<Opt> {
+        let blocks = Self::split_into_blocks_fast(content, slash_flags);
 
         // Use parallel iterator for processing multiple blocks
         // Only parallelize if we have enough blocks to benefit
-        if blocks.len() > 4 {
+        let opts: Vec<Opt> = if blocks.len() > 4 {
             blocks
                 .par_iter()
                 .flat_map(|block| {
-                    let opts = Parser::parse_line(block);
+                    let opts = Parser::parse_line_with(block, slash_flags);
                     opts.into_iter().collect::<Vec<_>>()
                 })
-                .collect::<Vec<_>>()
-                .into_iter()
                 .collect()
         } else {
             blocks
                 .iter()
-                .flat_map(|block| Parser::parse_line(block).into_iter())
+                .flat_map(|block| Parser::parse_line_with(block, slash_flags).into_iter())
                 .collect()
+        };
+
+        Self::merge_repeated(opts)
+    }
+
+    /// Collapse options with the same names into the first occurrence,
+    /// keeping the most informative description and any argument.
+    fn merge_repeated(opts: Vec<Opt>) -> EcoVec<Opt> {
+        let mut merged: Vec<Opt> = Vec::with_capacity(opts.len());
+        let mut index: std::collections::HashMap<
+            EcoVec<OptName>,
+            usize,
+            foldhash::fast::RandomState,
+        > = std::collections::HashMap::with_capacity_and_hasher(
+            opts.len(),
+            foldhash::fast::RandomState::default(),
+        );
+        for opt in opts {
+            match index.get(&opt.names) {
+                Some(&i) => {
+                    let kept = &mut merged[i];
+                    kept.merge_descriptions(&opt);
+                    if kept.argument.is_empty() {
+                        kept.argument = opt.argument;
+                    }
+                }
+                None => {
+                    index.insert(opt.names.clone(), merged.len());
+                    merged.push(opt);
+                }
+            }
         }
+        merged.into_iter().collect()
     }
 
     /// Preprocess content into option/description pairs, processing blocks in parallel.
     pub fn preprocess_blockwise(content: &str) -> EcoVec<(EcoString, EcoString)> {
-        let blocks = Self::split_into_blocks_fast(content);
+        let blocks = Self::split_into_blocks_fast(content, false);
 
         // Only parallelize if we have enough blocks
         if blocks.len() > 4 {
@@ -55,6 +402,40 @@ impl Layout {
         }
     }
 
+    /// Capture the prose before the first `Usage:`/`Options:` style section
+    /// as the command description, collapsed to a single line. Only the
+    /// first paragraph is kept; help that opens with a section yields an
+    /// empty description.
+    pub fn parse_description(content: &str) -> EcoString {
+        let mut words: Vec<&str> = Vec::new();
+
+        for line in Self::split_lines(content) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                if words.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            if Self::is_section_start(trimmed) {
+                break;
+            }
+            words.extend(trimmed.split_whitespace());
+        }
+
+        EcoString::from(words.join(" "))
+    }
+
+    /// Whether a trimmed line opens a usage, header or option section rather
+    /// than continuing prose.
+    pub(crate) fn is_section_start(trimmed: &str) -> bool {
+        let lower = trimmed.to_lowercase();
+        lower.starts_with("usage")
+            || lower.starts_with("synopsis")
+            || trimmed.starts_with('-')
+            || (trimmed.ends_with(':') && trimmed.split_whitespace().count() <= 3)
+    }
+
     pub fn parse_usage(content: &str) -> EcoString {
         let keywords = ["usage", "synopsis"];
         let bytes = content.as_bytes();
@@ -85,11 +466,7 @@ impl Layout {
             return EcoString::new();
         }
 
-        // Use bstr for SIMD-accelerated line iteration
-        let lines: Vec<&str> = bytes
-            .lines()
-            .filter_map(|line| std::str::from_utf8(line).ok())
-            .collect();
+        let lines = Self::split_lines(content);
 
         for (i, line) in lines.iter().enumerate() {
             let lower = line.to_lowercase();
@@ -119,11 +496,13 @@ impl Layout {
 
     /// Optimized block splitting that minimizes allocations
     /// Uses bstr for SIMD-accelerated line iteration
-    fn split_into_blocks_fast(content: &str) -> EcoVec<EcoString> {
+    fn split_into_blocks_fast(content: &str, slash_flags: bool) -> EcoVec<EcoString> {
+        let content = Self::strip_skipped_sections(content);
         let bytes = content.as_bytes();
 
-        // SIMD fast path: check if '-' exists at all
-        if memchr(b'-', bytes).is_none() {
+        // SIMD fast path: check if '-' (or '/' for `/flag` help, '+' for
+        // toggles) exists at all
+        if memchr3(b'-', b'/', b'+', bytes).is_none() {
             return EcoVec::new();
         }
 
@@ -143,7 +522,11 @@ impl Layout {
                     current_block.clear();
                     in_block = false;
                 }
-            } else if trimmed.starts_with('-') || in_block {
+            } else if trimmed.starts_with('-')
+                || in_block
+                || (slash_flags && Parser::starts_with_slash_flag(trimmed))
+                || Parser::starts_with_toggle(trimmed)
+            {
                 if !current_block.is_empty() {
                     current_block.push('\n');
                 }
@@ -181,14 +564,10 @@ impl Layout {
     }
 
     fn get_option_locations(s: &str, predicate: fn(&str) -> bool) -> EcoVec<(usize, usize)> {
-        let bytes = s.as_bytes();
-
-        // Use bstr for SIMD-accelerated line iteration
-        bytes
-            .lines()
+        Self::split_lines(s)
+            .into_iter()
             .enumerate()
-            .filter_map(|(i, line)| {
-                let line_str = std::str::from_utf8(line).ok()?;
+            .filter_map(|(i, line_str)| {
                 let trimmed = line_str.trim_start();
                 if !trimmed.is_empty() && predicate(trimmed) {
                     let offset = line_str.len() - trimmed.len();
@@ -233,6 +612,40 @@ impl Layout {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_extract_fenced_blocks_only_parses_help_fences() {
+        let markdown = "\
+# tool
+
+Run it with -x      to explode (prose, not an option).
+
+```text
+Usage: tool [OPTIONS]
+
+  -v, --verbose      Print more output
+```
+
+```rust
+  --not-an-option    Rust code
+```
+
+~~~console
+$ tool --help
+  -o, --output FILE  Write to FILE
+~~~
+";
+        let fenced = Layout::extract_fenced_blocks(markdown);
+        assert!(!fenced.contains("prose"));
+        assert!(!fenced.contains("Rust code"));
+
+        let opts = Layout::parse_blockwise(&fenced);
+        let names: Vec<&str> = opts
+            .iter()
+            .flat_map(|o| o.names.iter().map(|n| n.raw.as_str()))
+            .collect();
+        assert_eq!(names, ["--verbose", "-v", "--output", "-o"]);
+    }
+
     #[test]
     fn test_parse_usage() {
         let content = "usage: command [options]\n\ndescription";
@@ -240,6 +653,50 @@ mod tests {
         assert!(!usage.is_empty());
     }
 
+    #[test]
+    fn test_parse_description_takes_leading_prose() {
+        let content = "
+mytool - fetch and
+   transform remote   data
+
+Usage: mytool [OPTIONS] <URL>
+
+Options:
+  -v, --verbose    be verbose
+";
+        assert_eq!(
+            Layout::parse_description(content),
+            "mytool - fetch and transform remote data"
+        );
+
+        let content = "Usage: mytool [OPTIONS]\n\nFetch data.\n\nOptions:\n  -v  verbose\n";
+        assert_eq!(Layout::parse_description(content), "");
+    }
+
+    #[test]
+    fn test_parse_blockwise_merges_repeated_sections() {
+        let content = "\
+Summary:
+  -v, --verbose      verbose
+  -q, --quiet        quiet
+
+Options:
+  -v, --verbose      Print each file name as it is processed.
+  -o, --output FILE  Write to FILE
+";
+        let opts = Layout::parse_blockwise(content);
+        assert_eq!(opts.len(), 3);
+        let verbose: Vec<&Opt> = opts
+            .iter()
+            .filter(|opt| opt.names.iter().any(|n| n.raw == "--verbose"))
+            .collect();
+        assert_eq!(verbose.len(), 1);
+        assert_eq!(
+            verbose[0].description.as_str(),
+            "Print each file name as it is processed."
+        );
+    }
+
     #[test]
     fn test_parse_and_preprocess_blockwise() {
         let content = "\
@@ -266,4 +723,89 @@ mod tests {
         // both short and long options are aligned, so we should get a single offset
         assert_eq!(offsets.len(), 1);
     }
+
+    #[test]
+    fn test_parse_positionals() {
+        let clap = "Usage: tool [OPTIONS] <FILE> [MODE]\n\nArguments:\n  <FILE>  the input file\n  [MODE]  {fast,slow}\n\nOptions:\n  -v, --verbose  Verbose\n";
+        let positionals = Layout::parse_positionals(clap);
+        assert_eq!(positionals.len(), 2);
+        assert_eq!(positionals[0].name.as_str(), "<FILE>");
+        assert_eq!(positionals[0].description.as_str(), "the input file");
+        assert_eq!(positionals[1].name.as_str(), "[MODE]");
+
+        let wrapped = "ARGS:\n    <PATH>...\n            Files to process, read in\n            order\n\nFLAGS:\n    -h  Help\n";
+        let positionals = Layout::parse_positionals(wrapped);
+        assert_eq!(positionals.len(), 1);
+        assert_eq!(positionals[0].name.as_str(), "<PATH>...");
+        assert_eq!(
+            positionals[0].description.as_str(),
+            "Files to process, read in order"
+        );
+
+        assert!(Layout::parse_positionals("Options:\n  -v  Verbose\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_positionals_skips_prose() {
+        let help = "Arguments:\n  The following arguments are accepted:\n  <FILE>  Input file\n  Defaults to standard input.\n  MODE...  How to run\n";
+        let positionals = Layout::parse_positionals(help);
+        let names: Vec<&str> = positionals.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["<FILE>", "MODE..."]);
+        assert_eq!(
+            positionals[0].description.as_str(),
+            "Input file Defaults to standard input."
+        );
+    }
+
+    #[test]
+    fn test_group_headers() {
+        let content = "\
+Options:
+  -h, --help      Print help
+
+Network:
+  --proxy=URL
+  -t, --timeout SECS      Set the timeout
+";
+        let groups = Layout::group_headers(content);
+        assert_eq!(groups.get("--proxy").map(|h| h.as_str()), Some("Network"));
+        assert_eq!(groups.get("-t").map(|h| h.as_str()), Some("Network"));
+        assert!(!groups.contains_key("--help"));
+    }
+
+    #[test]
+    fn test_ascii_and_mixed_input_parse_identically() {
+        let ascii = "\
+  -a, --all        show all entries\r\n\
+  -n, --naive      use the naive sorter\n\
+\n\
+      --verbose    be verbose\n";
+        let mixed = ascii.replace("naive sorter", "na\u{ef}ve sorter");
+
+        assert_eq!(
+            Layout::split_lines(ascii),
+            ascii.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Layout::split_lines(&mixed),
+            mixed.lines().collect::<Vec<_>>()
+        );
+
+        let ascii_opts = Layout::parse_blockwise(ascii);
+        let mixed_opts = Layout::parse_blockwise(&mixed);
+        assert_eq!(ascii_opts.len(), 3);
+        assert_eq!(ascii_opts.len(), mixed_opts.len());
+        for (a, m) in ascii_opts.iter().zip(mixed_opts.iter()) {
+            assert_eq!(a.names, m.names);
+            assert_eq!(a.argument, m.argument);
+            assert_eq!(
+                a.description.replace("\u{ef}", "i"),
+                m.description.replace("\u{ef}", "i")
+            );
+        }
+        assert_eq!(
+            Layout::get_option_offsets(ascii),
+            Layout::get_option_offsets(&mixed)
+        );
+    }
 }
@@ -1,22 +1,31 @@
 pub mod cache;
+mod clap_export;
 pub mod cli;
 pub mod generators;
 pub mod io_handler;
 pub mod json_gen;
 pub mod layout;
+pub mod mdoc_parser;
 pub mod parser;
 pub mod postprocessor;
 pub mod subcommand_parser;
 pub mod types;
 
-pub use cache::{Cache, CacheEntry, CacheStats, DEFAULT_TTL_SECS};
-pub use cli::{Cli, Shell};
+pub use cache::{
+    CACHE_SCHEMA_VERSION, Cache, CacheEntry, CacheEntryInfo, CacheStats, DEFAULT_TTL_SECS,
+};
+pub use cli::{
+    Cli, CompletionsTarget, DescriptionMode, InputFormat, InstallLayout, RecipeFormat, Shell,
+};
 pub use generators::{
-    BashGenerator, ElvishGenerator, FishGenerator, NushellGenerator, ZshGenerator,
+    BashGenerator, CarapaceGenerator, ElvishGenerator, FishGenerator, GeneratorOptions,
+    MarkdownGenerator, NativeGenerator, NushellGenerator, OutputFormat, PowerShellGenerator,
+    TsvGenerator, ZshGenerator, generate, generate_with_options,
 };
-pub use io_handler::IoHandler;
-pub use json_gen::JsonGenerator;
+pub use io_handler::{CommandNotFound, IoHandler, NormalizeOptions};
+pub use json_gen::{Json5Generator, JsonGenerator};
 pub use layout::Layout;
+pub use mdoc_parser::MdocParser;
 pub use parser::Parser;
 pub use postprocessor::Postprocessor;
 pub use subcommand_parser::SubcommandParser;
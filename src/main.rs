@@ -1,17 +1,20 @@
-use clap::{FromArgMatches, crate_name};
+use clap::{FromArgMatches, ValueEnum, crate_name};
 use clap_complete::generate;
 use clap_complete::shells::{Bash, Elvish, Fish, PowerShell, Zsh};
 use clap_complete_nushell::Nushell;
+use d2o::generators::wrap_words;
 use d2o::{
-    BashGenerator, Cache, Cli, Command, ElvishGenerator, FishGenerator, IoHandler, JsonGenerator,
-    Layout, NushellGenerator, Postprocessor, Shell, SubcommandParser, ZshGenerator,
-    command_with_version,
+    BashGenerator, Cache, CacheEntryInfo, Cli, Command, CompletionsTarget, DescriptionMode,
+    FishGenerator, GeneratorOptions, InputFormat, InstallLayout, IoHandler, Json5Generator, Layout,
+    MdocParser, OutputFormat, Parser, Postprocessor, RecipeFormat, Shell, SubcommandParser,
+    ZshGenerator, command_with_version,
 };
 use ecow::EcoString;
+use regex::Regex;
 use std::io;
 use std::path::Path;
-use std::time::Duration;
-use tracing::debug;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 
 #[cfg(not(any(target_arch = "arm", target_os = "freebsd", target_family = "wasm")))]
 #[global_allocator]
@@ -44,21 +47,42 @@ async fn main() -> anyhow::Result<()> {
     let name = crate_name!();
     let mut stdout = io::stdout();
 
-    // Handle completions generation
-    if let Some(shell) = cli.completions {
-        match shell {
-            Shell::Bash => generate(Bash, &mut command, name, &mut stdout),
-            Shell::Fish => generate(Fish, &mut command, name, &mut stdout),
-            Shell::Zsh => generate(Zsh, &mut command, name, &mut stdout),
-            Shell::PowerShell => generate(PowerShell, &mut command, name, &mut stdout),
-            Shell::Elvish => generate(Elvish, &mut command, name, &mut stdout),
-            Shell::Nushell => generate(Nushell, &mut command, name, &mut stdout),
+    // Handle d2o's own completions; with an input source the shell is used
+    // as the output format instead (see Cli::effective_format)
+    if let Some(target) = cli.self_completions
+        && !cli.has_input_source()
+    {
+        let all = target.shell().is_none();
+        for (i, &shell) in target.shells().iter().enumerate() {
+            if all {
+                if i > 0 {
+                    println!();
+                }
+                println!("# ===== {} =====", shell.as_str());
+            }
+            match shell {
+                Shell::Bash => generate(Bash, &mut command, name, &mut stdout),
+                Shell::Fish => generate(Fish, &mut command, name, &mut stdout),
+                Shell::Zsh => generate(Zsh, &mut command, name, &mut stdout),
+                Shell::PowerShell => generate(PowerShell, &mut command, name, &mut stdout),
+                Shell::Elvish => generate(Elvish, &mut command, name, &mut stdout),
+                Shell::Nushell => generate(Nushell, &mut command, name, &mut stdout),
+            }
+        }
+        if let Some(shell) = cli.print_install_instructions {
+            eprintln!("{}", install_instructions(shell, name));
         }
         return Ok(());
     }
 
+    if cli.self_completions == Some(CompletionsTarget::All) {
+        anyhow::bail!(
+            "--completions all only generates d2o's own completions; use --format <SHELL> for the parsed command"
+        );
+    }
+
     // Handle cache operations
-    if cli.cache_clear || cli.cache_stats {
+    if cli.cache_clear || cli.cache_stats || cli.cache_list {
         let ttl = Duration::from_secs(cli.cache_ttl * 3600);
         let cache = Cache::with_ttl(ttl)?;
 
@@ -72,6 +96,16 @@ async fn main() -> anyhow::Result<()> {
             println!("{}", stats);
         }
 
+        if cli.cache_list {
+            print!("{}", format_cache_list(&cache.list().await?));
+        }
+
+        return Ok(());
+    }
+
+    if cli.seed_cache {
+        let count = seed_cache(&cli).await?;
+        println!("Seeded {} cache entries", count);
         return Ok(());
     }
 
@@ -98,54 +132,200 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Normal processing with optional caching
-    let cmd = if cli.loadjson.is_some() {
-        load_command_from_json(&cli).await?
+    let mut profile = Profile::default();
+    let cmd = if cli.loadjson.is_some() || cli.loadjson5.is_some() {
+        let started = Instant::now();
+        let cmd = load_command_from_json(&cli).await?;
+        profile.record("read", started);
+        cmd
     } else {
-        let content = get_input_content(&cli).await?;
-        build_command_with_cache(&cli, &content).await?
+        let started = Instant::now();
+        let content = read_input(&cli).await?;
+        profile.record("read", started);
+
+        let started = Instant::now();
+        let content = normalize_input(&cli, &content);
+        profile.record("normalize", started);
+
+        let started = Instant::now();
+        let cmd = build_command_with_cache(&cli, &content).await?;
+        let cmd = add_path_subcommands(&cli, cmd).await;
+        profile.record("parse", started);
+        cmd
     };
+    let cmd = prepare_for_output(&cli, cmd);
+    let options = generator_options(&cli);
+
+    if let (Some(layout), Some(prefix)) = (cli.install_layout, &cli.prefix) {
+        for path in install_completions(&cmd, &options, layout, Path::new(prefix)).await? {
+            println!("{}", path.display());
+        }
+        return Ok(());
+    }
 
-    let output = match format.as_str() {
-        "fish" => FishGenerator::generate(&cmd),
-        "zsh" => ZshGenerator::generate(&cmd),
-        "bash" => BashGenerator::generate_with_compat(&cmd, cli.bash_completion_compat),
-        "elvish" => ElvishGenerator::generate(&cmd),
-        "nushell" => NushellGenerator::generate(&cmd),
-        "json" => JsonGenerator::generate(&cmd),
-        "native" => format_native(&cmd),
-        _ => anyhow::bail!("Unknown output option"),
+    if let Some(tool) = cli.emit_install_recipe {
+        print!("{}", install_recipe(tool, &cmd.name, &recipe_args(&cli)));
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    let output = match format.parse::<OutputFormat>()? {
+        OutputFormat::Native if cli.pretty_native => {
+            format_native_pretty(&cmd, cli.max_line_width.unwrap_or_else(terminal_width))
+        }
+        output_format => d2o::generate_with_options(output_format, &cmd, &options),
+    };
+    let output = if cli.header {
+        with_header(&format, &cmd.name, output)
+    } else {
+        output
     };
+    profile.record("generate", started);
 
     if cli.write {
-        let path = write_output_to_cache(&cmd, &format, &output).await?;
+        let path = write_output_to_cache(&cmd, &format, &output, cli.overwrite).await?;
         println!("{}", path.display());
     } else {
         println!("{}", output);
     }
 
+    if cli.profile {
+        eprint!("{}", profile.summary());
+    }
+
+    if cli.verify
+        && let Some(result) = IoHandler::check_script_syntax(&format, &output).await
+    {
+        result?;
+        eprintln!("verify: {} syntax OK", format);
+    }
+
+    if let Some(shell) = cli.print_install_instructions {
+        eprintln!("{}", install_instructions(shell, &cmd.name));
+    }
+
     Ok(())
 }
 
+/// Step-by-step instructions for installing `name`'s completion in `shell`.
+fn install_instructions(shell: Shell, name: &str) -> String {
+    let home = std::env::home_dir().unwrap_or_else(|| std::path::PathBuf::from("~"));
+    let xdg = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .filter(|dir| !dir.is_empty())
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+    let config = xdg("XDG_CONFIG_HOME", ".config");
+    let data = xdg("XDG_DATA_HOME", ".local/share");
+    let format = shell.as_str();
+
+    let (dir, file, steps) = match shell {
+        Shell::Bash => (
+            data.join("bash-completion/completions"),
+            name.to_string(),
+            "bash-completion loads it on demand; start a new shell (exec bash) to use it."
+                .to_string(),
+        ),
+        Shell::Zsh => {
+            let dir = home.join(".zfunc");
+            let steps = format!(
+                "Add the directory to fpath before compinit runs in ~/.zshrc:\n\n    fpath=({} $fpath)\n    autoload -Uz compinit && compinit\n\nThen clear the cache and restart: rm -f ~/.zcompdump; exec zsh",
+                dir.display()
+            );
+            (dir, format!("_{}", name), steps)
+        }
+        Shell::Fish => (
+            config.join("fish/completions"),
+            format!("{}.fish", name),
+            "fish loads it automatically in new sessions.".to_string(),
+        ),
+        Shell::PowerShell => {
+            let dir = config.join("powershell/completions");
+            let steps = format!(
+                "Dot-source it from your profile ($PROFILE):\n\n    . {}",
+                dir.join(format!("{}.ps1", name)).display()
+            );
+            (dir, format!("{}.ps1", name), steps)
+        }
+        Shell::Elvish => (
+            config.join("elvish/lib"),
+            format!("{}.elv", name),
+            format!(
+                "Add `use {}` to {}.",
+                name,
+                config.join("elvish/rc.elv").display()
+            ),
+        ),
+        Shell::Nushell => {
+            let dir = config.join("nushell/completions");
+            let steps = format!(
+                "Add this line to config.nu:\n\n    use {} *",
+                dir.join(format!("{}.nu", name)).display()
+            );
+            (dir, format!("{}.nu", name), steps)
+        }
+    };
+
+    format!(
+        "To install the {} completion for {}, save the script as\n\n    {}\n\n(create the directory with: mkdir -p {})\n\n{}",
+        format,
+        name,
+        dir.join(file).display(),
+        dir.display(),
+        steps
+    )
+}
+
 async fn get_input_content(cli: &Cli) -> anyhow::Result<EcoString> {
-    let content = if let Some(json_file) = &cli.loadjson {
+    let content = read_input(cli).await?;
+    Ok(normalize_input(cli, &content))
+}
+
+/// Read the raw help text, man page or JSON for the selected input source.
+async fn read_input(cli: &Cli) -> anyhow::Result<EcoString> {
+    let content = if let Some(json_file) = cli.loadjson.as_ref().or(cli.loadjson5.as_ref()) {
         IoHandler::read_file(json_file).await?
     } else if let Some(file) = &cli.file {
         IoHandler::read_file(file).await?
     } else if let Some(cmd_name) = &cli.command {
-        if cli.skip_man || !IoHandler::is_man_available(cmd_name).await {
-            IoHandler::get_command_help(cmd_name).await?
+        if cli.require_command {
+            IoHandler::require_command(cmd_name).await?;
+        }
+        if let Some(args) = &cli.command_args {
+            IoHandler::get_command_help_with_variant(
+                &format!("{} {}", cmd_name, args),
+                cli.help_variant.as_deref(),
+                cli.lang.as_deref(),
+            )
+            .await?
+        } else if cli.skip_man || !IoHandler::is_man_available(cmd_name).await {
+            IoHandler::get_command_help_with_variant(
+                cmd_name,
+                cli.help_variant.as_deref(),
+                cli.lang.as_deref(),
+            )
+            .await?
         } else {
-            IoHandler::get_manpage(cmd_name).await?
+            IoHandler::get_manpage(cmd_name, cli.lang.as_deref()).await?
         }
     } else if let Some(subcommand) = &cli.subcommand {
         let (cmd, subcmd) = subcommand.split_once('-').ok_or_else(|| {
             anyhow::anyhow!("Subcommand format should be command-subcommand (e.g., git-log)")
         })?;
+        if cli.require_command {
+            IoHandler::require_command(cmd).await?;
+        }
 
         if cli.skip_man || !IoHandler::is_man_available(cmd).await {
-            IoHandler::get_command_help(&format!("{} {}", cmd, subcmd)).await?
+            IoHandler::get_command_help_with_variant(
+                &format!("{} {}", cmd, subcmd),
+                cli.help_variant.as_deref(),
+                cli.lang.as_deref(),
+            )
+            .await?
         } else {
-            IoHandler::get_manpage(&format!("{}-{}", cmd, subcmd)).await?
+            IoHandler::get_manpage(&format!("{}-{}", cmd, subcmd), cli.lang.as_deref()).await?
         }
     } else {
         return Err(anyhow::anyhow!(
@@ -153,19 +333,54 @@ async fn get_input_content(cli: &Cli) -> anyhow::Result<EcoString> {
         ));
     };
 
-    Ok(Postprocessor::unicode_spaces_to_ascii(
-        &Postprocessor::remove_bullets(&IoHandler::normalize_text(&content)),
-    ))
+    Ok(content)
+}
+
+/// Clean up raw help text (overstrike, tabs, bullets, Unicode spaces and
+/// dashes) before parsing.
+fn normalize_input(cli: &Cli, content: &str) -> EcoString {
+    let content = Postprocessor::strip_overstrike(content);
+    let content = Postprocessor::convert_tabs_to_spaces(&content, cli.tab_width);
+    let content = Postprocessor::remove_bullets(&IoHandler::normalize_text(&content));
+    Postprocessor::unicode_spaces_to_ascii(&Postprocessor::unicode_dashes_to_ascii(&content))
+}
+
+/// Wall-clock time spent in each phase of a run, printed by `--profile`.
+#[derive(Debug, Default)]
+struct Profile {
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Profile {
+    /// Record `phase` as having run from `started` until now.
+    fn record(&mut self, phase: &'static str, started: Instant) {
+        self.phases.push((phase, started.elapsed()));
+    }
+
+    fn summary(&self) -> String {
+        let total: Duration = self.phases.iter().map(|(_, elapsed)| *elapsed).sum();
+        let mut out = format!("{:<10}  {:>12}\n", "phase", "time");
+        for (phase, elapsed) in self.phases.iter().chain([&("total", total)]) {
+            out.push_str(&format!(
+                "{:<10}  {:>12}\n",
+                phase,
+                format!("{:.3?}", elapsed)
+            ));
+        }
+        out
+    }
 }
 
 fn build_command(cli: &Cli, content: &str) -> anyhow::Result<Command> {
     let name = if let Some(cmd_name) = &cli.command {
         EcoString::from(cmd_name.as_str())
     } else if let Some(file) = &cli.file {
+        // Help piped in with `--file -` has no file name to go by
         EcoString::from(
             Path::new(file)
                 .file_name()
                 .and_then(|n| n.to_str())
+                .filter(|_| file != IoHandler::STDIN_PATH)
                 .unwrap_or("command"),
         )
     } else if let Some(subcommand) = &cli.subcommand {
@@ -174,10 +389,36 @@ fn build_command(cli: &Cli, content: &str) -> anyhow::Result<Command> {
         EcoString::from("command")
     };
 
+    let rewritten;
+    let content = match cli.input_format {
+        InputFormat::Markdown => {
+            rewritten = Layout::extract_fenced_blocks(content);
+            rewritten.as_str()
+        }
+        InputFormat::PowerShell => {
+            rewritten = Parser::split_powershell_params(content);
+            rewritten.as_str()
+        }
+        _ => content,
+    };
+
     let mut cmd = Command::new(name.clone());
-    cmd.options = Layout::parse_blockwise(content);
     cmd.usage = Layout::parse_usage(content);
 
+    if cli.input_format == InputFormat::Man && MdocParser::is_mdoc(content) {
+        cmd.options = MdocParser::parse(content);
+        return Ok(cmd);
+    }
+
+    if cli.input_format != InputFormat::Man {
+        cmd.description = Layout::parse_description(content);
+    }
+    cmd.options = Layout::parse_blockwise_with(content, cli.input_format == InputFormat::Windows);
+    cmd.positionals = Layout::parse_positionals(content);
+    if cli.group_as_desc_fallback {
+        Layout::apply_group_descriptions(&mut cmd.options, content);
+    }
+
     let subcommand_candidates = SubcommandParser::parse(content);
     if cli.depth > 0 && !subcommand_candidates.is_empty() {
         for subcmd in subcommand_candidates.iter() {
@@ -186,8 +427,10 @@ fn build_command(cli: &Cli, content: &str) -> anyhow::Result<Command> {
                 description: subcmd.desc.clone(),
                 usage: EcoString::new(),
                 options: ecow::EcoVec::new(),
+                positionals: ecow::EcoVec::new(),
                 subcommands: ecow::EcoVec::new(),
                 version: EcoString::new(),
+                metadata: None,
             };
             cmd.subcommands.push(sub);
         }
@@ -196,8 +439,49 @@ fn build_command(cli: &Cli, content: &str) -> anyhow::Result<Command> {
     Ok(cmd)
 }
 
-/// Build a command with caching support.
-async fn build_command_with_cache(cli: &Cli, content: &str) -> anyhow::Result<Command> {
+/// Render cache entries as an aligned table for `--cache-list`.
+fn format_cache_list(entries: &[CacheEntryInfo]) -> String {
+    let key_width = entries
+        .iter()
+        .map(|e| e.key.chars().count())
+        .chain([3])
+        .max()
+        .unwrap_or(3);
+    let name_width = entries
+        .iter()
+        .map(|e| e.name.chars().count())
+        .chain([7])
+        .max()
+        .unwrap_or(7);
+
+    let mut out = format!(
+        "{:key_width$}  {:name_width$}  {:>6}  {:>8}  {:>5}  {:>5}  STATUS\n",
+        "KEY", "COMMAND", "AGE", "SIZE", "OPTS", "SUBS"
+    );
+    for entry in entries {
+        let age = entry.age_secs();
+        let age = match age {
+            0..60 => format!("{}s", age),
+            60..3600 => format!("{}m", age / 60),
+            3600..86400 => format!("{}h", age / 3600),
+            _ => format!("{}d", age / 86400),
+        };
+        out.push_str(&format!(
+            "{:key_width$}  {:name_width$}  {:>6}  {:>8}  {:>5}  {:>5}  {}\n",
+            entry.key,
+            entry.name,
+            age,
+            entry.size_bytes,
+            entry.options,
+            entry.subcommands,
+            if entry.valid { "valid" } else { "expired" }
+        ));
+    }
+    out
+}
+
+/// Name and source identifier under which the current input is cached.
+fn cache_identity(cli: &Cli) -> (&str, Option<String>) {
     // Determine command name for cache key
     let name = cli
         .command
@@ -206,40 +490,77 @@ async fn build_command_with_cache(cli: &Cli, content: &str) -> anyhow::Result<Co
         .or_else(|| {
             cli.file
                 .as_ref()
+                .filter(|f| *f != IoHandler::STDIN_PATH)
                 .and_then(|f| Path::new(f).file_name()?.to_str())
         })
         .unwrap_or("command");
 
     // Determine source identifier for cache key
-    let source = if cli.command.is_some() || cli.subcommand.is_some() {
+    let source = if let Some(args) = &cli.command_args {
+        Some(format!("{} --help", args))
+    } else if cli.command.is_some() || cli.subcommand.is_some() {
         if cli.skip_man {
-            Some("--help")
+            Some(String::from("--help"))
         } else {
-            Some("man")
+            Some(String::from("man"))
         }
     } else {
-        cli.file.as_deref()
+        cli.file.clone()
+    };
+
+    // Parses of the same text in another input format must not share an entry
+    let source = match cli.input_format {
+        InputFormat::Text => source,
+        format => Some(format!(
+            "{}#{}",
+            source.unwrap_or_default(),
+            format.as_str()
+        )),
     };
 
+    (name, source)
+}
+
+/// Build a command with caching support.
+async fn build_command_with_cache(cli: &Cli, content: &str) -> anyhow::Result<Command> {
+    let (name, source) = cache_identity(cli);
+    let source = source.as_deref();
+    let redact = redact_patterns(cli)?;
+
     let content_hash = Cache::hash_content(content);
 
     // Try cache if enabled
     if cli.cache {
         let ttl = Duration::from_secs(cli.cache_ttl * 3600);
         if let Ok(cache) = Cache::with_ttl(ttl) {
+            let version = IoHandler::parse_version(content);
+            let version = version.as_deref();
+
             // Try to get from cache
-            if let Some(cached_cmd) = cache.get(name, source, content_hash).await {
+            if let Some(cached_cmd) = cache
+                .get_versioned(name, source, version, content_hash)
+                .await
+            {
                 debug!("Cache hit for command: {}", name);
-                return Ok(cached_cmd);
+                if cli.cache_refresh_on_hit
+                    && let Err(e) = cache.touch(name, source, version).await
+                {
+                    debug!("Failed to refresh cache entry: {}", e);
+                }
+                // Entries cached before a pattern was added are masked too
+                return Ok(Postprocessor::redact(cached_cmd, &redact));
             }
 
             // Parse and cache the result
             debug!("Cache miss for command: {}, parsing...", name);
             let cmd = build_command(cli, content)?;
-            let cmd = Postprocessor::fix_command(cmd);
+            let cmd = Postprocessor::redact(Postprocessor::fix_command(cmd), &redact);
 
             // Store in cache (ignore errors, caching is best-effort)
-            if let Err(e) = cache.set(name, source, content_hash, &cmd).await {
+            if let Err(e) = cache
+                .set_versioned(name, source, version, content_hash, &cmd)
+                .await
+            {
                 debug!("Failed to cache command: {}", e);
             }
 
@@ -249,50 +570,472 @@ async fn build_command_with_cache(cli: &Cli, content: &str) -> anyhow::Result<Co
 
     // Caching disabled or failed to initialize
     let cmd = build_command(cli, content)?;
-    Ok(Postprocessor::fix_command(cmd))
+    Ok(Postprocessor::redact(
+        Postprocessor::fix_command(cmd),
+        &redact,
+    ))
+}
+
+/// Compile the `--redact` patterns.
+fn redact_patterns(cli: &Cli) -> anyhow::Result<Vec<Regex>> {
+    cli.redact
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid --redact pattern {:?}: {}", pattern, e))
+        })
+        .collect()
+}
+
+/// Parse every input (the given source, or each command listed in
+/// `--commands-file`) and store it in the cache without generating output.
+async fn seed_cache(cli: &Cli) -> anyhow::Result<usize> {
+    if !cli.has_input_source() {
+        return Err(anyhow::anyhow!(
+            "No input source specified for --seed-cache. Use --command, --file, --subcommand, or --commands-file"
+        ));
+    }
+    let inputs = match &cli.commands_file {
+        Some(path) => {
+            let list = IoHandler::read_file(path).await?;
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|name| Cli {
+                    command: Some(name.to_string()),
+                    commands_file: None,
+                    ..cli.clone()
+                })
+                .collect()
+        }
+        None => vec![cli.clone()],
+    };
+
+    let ttl = Duration::from_secs(cli.cache_ttl * 3600);
+    let cache = Cache::with_ttl(ttl)?;
+    let redact = redact_patterns(cli)?;
+    let mut written = 0;
+
+    for input in inputs.iter() {
+        let content = match get_input_content(input).await {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Skipping {}: {}", input.get_input().unwrap_or("input"), e);
+                continue;
+            }
+        };
+
+        let cmd = Postprocessor::redact(
+            Postprocessor::fix_command(build_command(input, &content)?),
+            &redact,
+        );
+        let (name, source) = cache_identity(input);
+        let version = IoHandler::parse_version(&content);
+        cache
+            .set_versioned(
+                name,
+                source.as_deref(),
+                version.as_deref(),
+                Cache::hash_content(&content),
+                &cmd,
+            )
+            .await?;
+        written += 1;
+    }
+
+    Ok(written)
 }
 
 async fn load_command_from_json(cli: &Cli) -> anyhow::Result<Command> {
+    if let Some(json5_file) = &cli.loadjson5 {
+        let content = IoHandler::read_file(json5_file).await?;
+        return Ok(Postprocessor::fix_command(Json5Generator::parse(&content)?));
+    }
+
     let json_file = cli
         .loadjson
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("No JSON file specified"))?;
     let content = IoHandler::read_file(json_file).await?;
-    let mut cmd: Command = serde_json::from_str(&content)?;
+    let mut cmd = parse_command_json(cli, &content)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", json_file, e))?;
+    for path in &cli.merge_json {
+        let content = IoHandler::read_file(path).await?;
+        let other = parse_command_json(cli, &content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path, e))?;
+        cmd.merge(other);
+    }
     cmd = Postprocessor::fix_command(cmd);
     Ok(cmd)
 }
 
-fn format_native(cmd: &Command) -> EcoString {
-    let mut output = Vec::new();
+fn parse_command_json(cli: &Cli, content: &str) -> serde_json::Result<Command> {
+    if cli.strict_json {
+        Command::from_json_strict(content)
+    } else {
+        serde_json::from_str(content)
+    }
+}
+
+fn generator_options(cli: &Cli) -> GeneratorOptions {
+    GeneratorOptions {
+        bash_completion_compat: cli.bash_completion_compat,
+        json_typed: cli.json_typed,
+        json_sorted: cli.json_sorted,
+        json_include_empty_sections: cli.include_empty_sections,
+        max_desc_len: cli.max_desc_len,
+        description_mode: cli.description_mode,
+        bundle_shorts: cli.bundle_shorts,
+        bash_nospace: cli.bash_nospace,
+        bash_function_prefix: cli.bash_function_prefix.as_deref().map(EcoString::from),
+        also_complete: cli.also_complete.iter().map(EcoString::from).collect(),
+        elvish_module: cli.elvish_module.as_deref().map(EcoString::from),
+        zsh_compdef_name: cli.zsh_compdef_name.as_deref().map(EcoString::from),
+        preserve_order: cli.preserve_order,
+        max_line_width: cli.max_line_width,
+    }
+}
 
-    output.push(format!("Name:  {}", cmd.name));
-    output.push(format!("Desc:  {}", cmd.description));
-    output.push(format!("Usage:\n{}", cmd.usage));
+/// With `--path-subcommands`, add `<command>-<name>` executables on PATH as
+/// subcommands that the help text did not already list.
+async fn add_path_subcommands(cli: &Cli, mut cmd: Command) -> Command {
+    let Some(base) = cli.command.as_deref().filter(|_| cli.path_subcommands) else {
+        return cmd;
+    };
 
-    for opt in cmd.options.iter() {
-        output.push(format!(
-            "  {} ({})",
-            opt.names
-                .iter()
-                .map(|n| n.raw.to_string())
-                .collect::<Vec<_>>()
-                .join(", "),
-            opt.argument
+    let mut names = IoHandler::path_subcommands(base, None).await;
+    if cli.exclude_builtin {
+        IoHandler::exclude_builtins(base, &mut names);
+    }
+    for name in names {
+        if cmd.find_subcommand(&[name.as_str()]).is_none() {
+            cmd.subcommands.push(Command::new(name));
+        }
+    }
+    cmd
+}
+
+/// Apply output-only transformations that must not leak into cached entries.
+fn prepare_for_output(cli: &Cli, mut cmd: Command) -> Command {
+    if cli.reclassify {
+        cmd = Postprocessor::reclassify_names(cmd);
+    }
+    if cli.normalize {
+        cmd = Postprocessor::normalize_tree(cmd);
+    }
+    if cli.ascii_only {
+        cmd = Postprocessor::ascii_only(cmd);
+    }
+    if cli.flatten_options {
+        cmd = cmd.flatten_into_options_only();
+    }
+    cmd
+}
+
+/// Prepend a `Generated by d2o` comment in the syntax of `format`. Formats
+/// without comments are returned unchanged.
+fn with_header(format: &str, name: &str, output: EcoString) -> EcoString {
+    let comment = match format {
+        "bash" | "zsh" | "fish" | "elvish" | "nushell" | "powershell" => "#",
+        "json5" => "//",
+        _ => return output,
+    };
+
+    let mut out = format!(
+        "{} Generated by d2o {} for {}\n",
+        comment,
+        d2o::build::PKG_VERSION,
+        name
+    );
+    // zsh reads `#compdef` only from the first line, so keep it there
+    match output.split_once('\n') {
+        Some((first, rest)) if format == "zsh" && first.starts_with("#compdef") => {
+            out.insert_str(0, &format!("{}\n", first));
+            out.push_str(rest);
+        }
+        _ => out.push_str(&output),
+    }
+    EcoString::from(out)
+}
+
+/// Width used for `--pretty-native`, falling back to 80 columns when
+/// stdout is not a terminal.
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(w), _)| w as usize)
+        .unwrap_or(80)
+}
+
+/// Render a command as an aligned table: names padded to a shared column
+/// with descriptions wrapped to `width`.
+fn format_native_pretty(cmd: &Command, width: usize) -> EcoString {
+    let mut out = String::new();
+
+    out.push_str(&cmd.name);
+    if !cmd.description.is_empty() {
+        out.push_str(" - ");
+        out.push_str(&cmd.description);
+    }
+    out.push('\n');
+    if !cmd.usage.is_empty() {
+        out.push('\n');
+        out.push_str(cmd.usage.trim());
+        out.push('\n');
+    }
+
+    let options: Vec<(String, &str)> = cmd
+        .options
+        .iter()
+        .map(|opt| {
+            let mut left = opt.names_joined(", ");
+            if !opt.argument.is_empty() {
+                left.push(' ');
+                left.push_str(&opt.argument);
+            }
+            (left, opt.description.as_str())
+        })
+        .collect();
+    let subcommands: Vec<(String, &str)> = cmd
+        .subcommands
+        .iter()
+        .map(|sub| (sub.name.to_string(), sub.description.as_str()))
+        .collect();
+
+    for (title, rows) in [("Options", options), ("Subcommands", subcommands)] {
+        if rows.is_empty() {
+            continue;
+        }
+        out.push('\n');
+        out.push_str(title);
+        out.push_str(":\n");
+        write_table(&mut out, &rows, width);
+    }
+
+    EcoString::from(out)
+}
+
+fn write_table(out: &mut String, rows: &[(String, &str)], width: usize) {
+    const INDENT: usize = 2;
+    const GAP: usize = 2;
+
+    // Keep at least half the line for descriptions; longer names get their
+    // description on the following line instead
+    let longest = rows.iter().map(|(left, _)| left.chars().count()).max();
+    let column = (INDENT + longest.unwrap_or(0) + GAP).min(width / 2);
+    let desc_width = width.saturating_sub(column).max(20);
+
+    for (left, desc) in rows {
+        let mut line = format!("{:INDENT$}{}", "", left);
+        let lines = wrap_words(desc, desc_width);
+        let mut lines = lines.iter();
+
+        if line.chars().count() + GAP > column {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        if let Some(first) = lines.next() {
+            let pad = column - line.chars().count();
+            line.push_str(&" ".repeat(pad));
+            line.push_str(first);
+        }
+        if !line.is_empty() {
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        for rest in lines {
+            out.push_str(&" ".repeat(column));
+            out.push_str(rest);
+            out.push('\n');
+        }
+    }
+}
+
+/// Relative path of a shell's completion file within an install layout.
+/// Path separators in `name` are replaced, as in cache keys, and so is a
+/// name that is empty or only dots, so the file always stays in the layout's directory.
+fn install_path(layout: InstallLayout, shell: Shell, name: &str) -> std::path::PathBuf {
+    let mut name = name.replace(['/', '\\', ':'], "_");
+    if name.chars().all(|c| c == '.') {
+        name = "_".repeat(name.len().max(1));
+    }
+    let name = name.as_str();
+    match (layout, shell) {
+        (InstallLayout::Vendor, Shell::Bash) => ["share", "bash-completion", "completions", name]
+            .iter()
+            .collect(),
+        (InstallLayout::Vendor, Shell::Zsh) => ["share", "zsh", "site-functions"]
+            .iter()
+            .collect::<std::path::PathBuf>()
+            .join(format!("_{}", name)),
+        (InstallLayout::Vendor, _) => ["share", "fish", "vendor_completions.d"]
+            .iter()
+            .collect::<std::path::PathBuf>()
+            .join(format!("{}.fish", name)),
+    }
+}
+
+/// The input and shell output flags of this run, shell-quoted, so an
+/// install recipe regenerates the completions from the same source.
+fn recipe_args(cli: &Cli) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut push = |flag: &str, value: Option<&str>| {
+        args.push(flag.to_string());
+        args.extend(value.map(shell_quote));
+    };
+
+    let sources = [
+        ("--command", &cli.command),
+        ("--subcommand", &cli.subcommand),
+        ("--file", &cli.file),
+        ("--loadjson", &cli.loadjson),
+        ("--loadjson5", &cli.loadjson5),
+        ("--command-args", &cli.command_args),
+        ("--help-variant", &cli.help_variant),
+        ("--lang", &cli.lang),
+        ("--bash-function-prefix", &cli.bash_function_prefix),
+        ("--zsh-compdef-name", &cli.zsh_compdef_name),
+    ];
+    for (flag, value) in sources {
+        if let Some(value) = value {
+            push(flag, Some(value));
+        }
+    }
+    for path in &cli.merge_json {
+        push("--merge-json", Some(path));
+    }
+    for name in &cli.also_complete {
+        push("--also-complete", Some(name));
+    }
+    if cli.input_format != InputFormat::Text {
+        push("--input-format", Some(cli.input_format.as_str()));
+    }
+    if let Some(len) = cli.max_desc_len {
+        push("--max-desc-len", Some(&len.to_string()));
+    }
+    if cli.description_mode != DescriptionMode::default()
+        && let Some(mode) = cli.description_mode.to_possible_value()
+    {
+        push("--completion-description-mode", Some(mode.get_name()));
+    }
+    if !cli.bash_nospace {
+        push("--bash-nospace", Some("false"));
+    }
+    let switches = [
+        ("--strict-json", cli.strict_json),
+        ("--skip-man", cli.skip_man),
+        ("--bash-completion-compat", cli.bash_completion_compat),
+        ("--bundle-shorts", cli.bundle_shorts),
+    ];
+    for (flag, set) in switches {
+        if set {
+            push(flag, None);
+        }
+    }
+    args
+}
+
+/// Quote `word` for a POSIX shell unless it needs no quoting.
+fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./=:@%+,".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// A make or just recipe that runs d2o with `args` to regenerate `name`'s
+/// bash, zsh and fish completions and installs them into the vendor layout.
+fn install_recipe(tool: RecipeFormat, name: &str, args: &[String]) -> String {
+    let shells = [Shell::Bash, Shell::Zsh, Shell::Fish];
+    let target = |shell: Shell| format!("install-completions-{}", shell.as_str());
+    let targets: Vec<String> = shells.iter().map(|&shell| target(shell)).collect();
+    let root = match tool {
+        RecipeFormat::Make => "$(DESTDIR)$(PREFIX)",
+        RecipeFormat::Just => "{{prefix}}",
+    };
+
+    let mut out = match tool {
+        RecipeFormat::Make => format!(
+            "PREFIX ?= /usr/local\n\n.PHONY: install-completions {}\n\n",
+            targets.join(" ")
+        ),
+        RecipeFormat::Just => String::from("prefix := \"/usr/local\"\n\n"),
+    };
+    out.push_str(&format!(
+        "# Install {} completions for bash, zsh and fish\ninstall-completions: {}\n",
+        name,
+        targets.join(" ")
+    ));
+
+    let indent = match tool {
+        RecipeFormat::Make => "\t",
+        RecipeFormat::Just => "    ",
+    };
+    // `$` and `{{` are the tools' own interpolation
+    let args = args.join(" ");
+    let args = match tool {
+        RecipeFormat::Make => args.replace('$', "$$"),
+        RecipeFormat::Just => args.replace("{{", "{{{{"),
+    };
+    for shell in shells {
+        let relative = install_path(InstallLayout::Vendor, shell, name);
+        let relative: Vec<_> = relative.iter().map(|part| part.to_string_lossy()).collect();
+        let (file, dirs) = relative.split_last().expect("install path has a file name");
+        let dir = format!("{}/{}", root, dirs.join("/"));
+        out.push_str(&format!(
+            "\n{}:\n{indent}mkdir -p \"{}\"\n{indent}d2o {} --format {} > \"{}/{}\"\n",
+            target(shell),
+            dir,
+            args,
+            shell.as_str(),
+            dir,
+            file,
         ));
     }
 
-    for subcmd in cmd.subcommands.iter() {
-        output.push(format!("Subcommand: {}", subcmd.name));
+    out
+}
+
+/// Write bash, zsh and fish completions under `prefix`, returning the
+/// written paths.
+async fn install_completions(
+    cmd: &Command,
+    options: &GeneratorOptions,
+    layout: InstallLayout,
+    prefix: &Path,
+) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    let mut written = Vec::new();
+
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let script = match shell {
+            Shell::Bash => BashGenerator::generate_with_options(cmd, options),
+            Shell::Zsh => ZshGenerator::generate_with_options(cmd, options),
+            _ => FishGenerator::generate_with_options(cmd, options),
+        };
+
+        let path = prefix.join(install_path(layout, shell, &cmd.name));
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&path, script.as_str())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+        written.push(path);
     }
 
-    EcoString::from(output.join("\n\n"))
+    Ok(written)
 }
 
 async fn write_output_to_cache(
     cmd: &Command,
     format: &str,
     output: &str,
+    overwrite: bool,
 ) -> anyhow::Result<std::path::PathBuf> {
     let home = std::env::home_dir()
         .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
@@ -305,6 +1048,12 @@ async fn write_output_to_cache(
     let mut path = dir.clone();
     path.push(file_name);
 
+    if !overwrite && tokio::fs::try_exists(&path).await? {
+        anyhow::bail!(
+            "Refusing to overwrite existing file: {} (pass --overwrite true to replace it)",
+            path.display()
+        );
+    }
     tokio::fs::write(&path, output).await?;
 
     Ok(path)
@@ -313,7 +1062,7 @@ async fn write_output_to_cache(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use d2o::cli::DEFAULT_CACHE_TTL_HOURS;
+    use d2o::cli::{DEFAULT_CACHE_TTL_HOURS, DEFAULT_TAB_WIDTH};
     use ecow::EcoVec;
 
     /// Helper to create a default Cli for testing
@@ -323,19 +1072,62 @@ mod tests {
             file: None,
             subcommand: None,
             loadjson: None,
+            strict_json: false,
+            merge_json: Vec::new(),
+            loadjson5: None,
+            input_format: InputFormat::Text,
             format: "native".to_string(),
             json: false,
+            json_typed: false,
+            json_sorted: false,
+            include_empty_sections: false,
+            pretty_native: false,
+            max_line_width: None,
+            header: false,
+            require_command: true,
             skip_man: false,
+            lang: None,
+            command_args: None,
+            help_variant: None,
+            group_as_desc_fallback: false,
             list_subcommands: false,
             debug: false,
+            tab_width: DEFAULT_TAB_WIDTH,
             depth: 4,
-            completions: None,
+            path_subcommands: false,
+            exclude_builtin: false,
+            self_completions: None,
+            print_install_instructions: None,
             write: false,
+            verify: false,
+            profile: false,
+            overwrite: true,
+            install_layout: None,
+            prefix: None,
+            emit_install_recipe: None,
             bash_completion_compat: false,
+            bundle_shorts: false,
+            bash_nospace: true,
+            bash_function_prefix: None,
+            also_complete: Vec::new(),
+            elvish_module: None,
+            zsh_compdef_name: None,
+            max_desc_len: None,
+            description_mode: DescriptionMode::FirstSentence,
+            redact: Vec::new(),
+            flatten_options: false,
+            preserve_order: false,
+            reclassify: false,
+            ascii_only: false,
+            normalize: false,
             cache: false, // Disable cache in tests by default
             cache_ttl: DEFAULT_CACHE_TTL_HOURS,
+            cache_refresh_on_hit: false,
+            seed_cache: false,
+            commands_file: None,
             cache_clear: false,
             cache_stats: false,
+            cache_list: false,
             verbosity: Default::default(),
         }
     }
@@ -390,11 +1182,17 @@ mod tests {
                     },
                     argument: EcoString::new(),
                     description: EcoString::from("Verbose"),
+                    multiple: false,
+                    toggle: false,
+                    optional_argument: false,
+                    value_range: None,
                 });
                 v
             },
+            positionals: EcoVec::new(),
             subcommands: EcoVec::new(),
             version: EcoString::new(),
+            metadata: None,
         };
 
         let json = serde_json::to_string(&cmd).unwrap();
@@ -415,6 +1213,65 @@ mod tests {
         assert_eq!(loaded.options[0].description.as_str(), "Verbose");
     }
 
+    #[tokio::test]
+    async fn test_load_command_from_json_keeps_deep_tree() {
+        use std::io::Write;
+
+        let level = |name: &str, flag: &str| {
+            let mut cmd = Command::new(EcoString::from(name));
+            cmd.usage = EcoString::from(format!("{} [OPTIONS]", name));
+            cmd.options = d2o::Parser::parse_line(&format!("  {}      Flag of {}", flag, name));
+            cmd
+        };
+        let mut leaf = level("add", "--track");
+        leaf.options
+            .push(d2o::Parser::parse_line("  -f, --force      Overwrite")[0].clone());
+        let mut middle = level("remote", "--verbose");
+        middle.subcommands.push(leaf);
+        let mut root = level("git", "--version");
+        root.subcommands.push(middle);
+
+        let mut tmp = tempfile::NamedTempFile::new().expect("create json temp file");
+        write!(tmp, "{}", d2o::JsonGenerator::generate(&root)).unwrap();
+
+        let cli = Cli {
+            loadjson: Some(tmp.path().to_str().unwrap().to_string()),
+            ..test_cli()
+        };
+        let loaded = load_command_from_json(&cli).await.expect("load from json");
+
+        let leaf = &loaded.subcommands[0].subcommands[0];
+        assert_eq!(leaf.name.as_str(), "add");
+        assert_eq!(leaf.options.len(), 2);
+        assert_eq!(leaf.options[0].description.as_str(), "Flag of add");
+        assert_eq!(leaf.options[1].names.len(), 2);
+        assert_eq!(
+            loaded.subcommands[0].options[0].names[0].raw.as_str(),
+            "--verbose"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_command_from_json5() {
+        use std::io::Write;
+
+        let mut tmp = tempfile::NamedTempFile::new().expect("create json5 temp file");
+        write!(
+            tmp,
+            "// edited by hand\n{{ name: 'json5cmd', description: '', usage: '',\n  options: [{{ names: ['-v'], argument: '', description: 'Verbose', }},], }}"
+        )
+        .unwrap();
+
+        let cli = Cli {
+            loadjson5: Some(tmp.path().to_str().unwrap().to_string()),
+            ..test_cli()
+        };
+
+        let loaded = load_command_from_json(&cli).await.expect("load from json5");
+        assert_eq!(loaded.name.as_str(), "json5cmd");
+        assert_eq!(loaded.options[0].description.as_str(), "Verbose");
+    }
+
     #[test]
     fn test_build_command_uses_command_name_and_parses_options() {
         let cli = Cli {
@@ -434,6 +1291,45 @@ mod tests {
         assert!(names.contains(&"--verbose".to_string()));
     }
 
+    #[test]
+    fn test_build_command_reads_slash_flags_only_for_windows_help() {
+        let help = "FILES\n  /etc      Configuration\n\nOPTIONS:\n  -v      Be verbose";
+        let cmd = build_command(&test_cli(), help).expect("build command");
+        assert_eq!(cmd.options.len(), 1);
+
+        let cli = Cli {
+            input_format: InputFormat::Windows,
+            ..test_cli()
+        };
+        let cmd = build_command(&cli, help).expect("build command");
+        assert!(cmd.options.iter().any(|opt| opt.names[0].raw == "/etc"));
+    }
+
+    #[test]
+    fn test_build_command_group_as_desc_fallback() {
+        let help =
+            "Usage: mycmd [OPTIONS]\n\nNetwork:\n  --proxy\n  --timeout SECS      Set the timeout";
+        let cli = Cli {
+            group_as_desc_fallback: true,
+            ..test_cli()
+        };
+
+        let cmd = Postprocessor::fix_command(build_command(&cli, help).expect("build command"));
+        let proxy = cmd
+            .options
+            .iter()
+            .find(|opt| opt.names[0].raw.as_str() == "--proxy")
+            .expect("--proxy kept");
+        assert_eq!(proxy.description.as_str(), "Network");
+
+        let cmd = Postprocessor::fix_command(build_command(&test_cli(), help).unwrap());
+        assert!(
+            cmd.options
+                .iter()
+                .all(|opt| opt.names[0].raw.as_str() != "--proxy")
+        );
+    }
+
     #[test]
     fn test_build_command_name_from_file_and_subcommands() {
         let cli = Cli {
@@ -453,43 +1349,182 @@ mod tests {
     }
 
     #[test]
-    fn test_format_native_includes_fields() {
+    fn test_install_instructions_zsh() {
+        let steps = install_instructions(Shell::Zsh, "git");
+        assert!(steps.contains("fpath=("), "{}", steps);
+        assert!(steps.contains("compinit"));
+        assert!(steps.contains("_git"));
+
+        let fish = install_instructions(Shell::Fish, "git");
+        assert!(fish.contains("fish/completions"));
+        assert!(fish.contains("git.fish"));
+    }
+
+    #[test]
+    fn test_install_recipe_just_has_target_per_shell() {
+        let args = recipe_args(&Cli {
+            command: Some("git".to_string()),
+            ..test_cli()
+        });
+        let recipe = install_recipe(RecipeFormat::Just, "git", &args);
+        assert!(recipe.starts_with("prefix := \"/usr/local\""), "{}", recipe);
+        assert!(recipe.contains(
+            "install-completions: install-completions-bash install-completions-zsh install-completions-fish"
+        ));
+        for (shell, path) in [
+            ("bash", "{{prefix}}/share/bash-completion/completions/git"),
+            ("zsh", "{{prefix}}/share/zsh/site-functions/_git"),
+            (
+                "fish",
+                "{{prefix}}/share/fish/vendor_completions.d/git.fish",
+            ),
+        ] {
+            assert!(
+                recipe.contains(&format!("\ninstall-completions-{}:\n", shell)),
+                "{}",
+                recipe
+            );
+            assert!(recipe.contains(&format!(
+                "    d2o --command git --format {} > \"{}\"",
+                shell, path
+            )));
+        }
+
+        let make = install_recipe(RecipeFormat::Make, "git", &args);
+        assert!(make.contains(".PHONY: install-completions"));
+        assert!(make.contains("\tmkdir -p \"$(DESTDIR)$(PREFIX)/share/zsh/site-functions\""));
+    }
+
+    #[test]
+    fn test_install_path_stays_in_layout_directory() {
+        let bash = install_path(InstallLayout::Vendor, Shell::Bash, "../../etc/evil");
+        assert_eq!(
+            bash,
+            Path::new("share/bash-completion/completions/.._.._etc_evil")
+        );
+        let zsh = install_path(InstallLayout::Vendor, Shell::Zsh, "a\\b:c");
+        assert_eq!(zsh, Path::new("share/zsh/site-functions/_a_b_c"));
+        let bash = install_path(InstallLayout::Vendor, Shell::Bash, "..");
+        assert_eq!(bash, Path::new("share/bash-completion/completions/__"));
+    }
+
+    #[test]
+    fn test_install_recipe_passes_input_flags_through() {
+        let cli = Cli {
+            file: Some("docs/my tool's $help.txt".to_string()),
+            input_format: InputFormat::Windows,
+            bash_nospace: false,
+            also_complete: vec!["mt".to_string()],
+            ..test_cli()
+        };
+        let args = recipe_args(&cli);
+        assert_eq!(
+            args.join(" "),
+            "--file 'docs/my tool'\\''s $help.txt' --also-complete mt --input-format windows --bash-nospace false"
+        );
+
+        let make = install_recipe(RecipeFormat::Make, "tool", &args);
+        assert!(make.contains("\td2o --file 'docs/my tool'\\''s $$help.txt' --also-complete mt"));
+        let just = install_recipe(RecipeFormat::Just, "tool", &args);
+        assert!(just.contains("    d2o --file 'docs/my tool'\\''s $help.txt' --also-complete mt"));
+    }
+
+    #[test]
+    fn test_with_header() {
+        let fish = EcoString::from("complete -c git -l 'verbose'\n");
+        let version = d2o::build::PKG_VERSION;
+
+        let out = with_header("fish", "git", fish.clone());
+        assert_eq!(
+            out.lines().next(),
+            Some(format!("# Generated by d2o {} for git", version).as_str())
+        );
+        assert!(out.ends_with(fish.as_str()));
+
+        let zsh = with_header("zsh", "git", EcoString::from("#compdef git\n\n_git() {}\n"));
+        let mut lines = zsh.lines();
+        assert_eq!(lines.next(), Some("#compdef git"));
+        assert!(lines.next().unwrap().contains(version));
+
+        let json = EcoString::from("{}");
+        assert_eq!(with_header("json", "git", json.clone()), json);
+    }
+
+    #[test]
+    fn test_format_native_pretty_aligns_columns() {
         let mut cmd = Command::new(EcoString::from("test"));
-        cmd.description = EcoString::from("Test command");
-        cmd.usage = EcoString::from("test [OPTIONS]");
+        for (name, arg, desc) in [
+            ("-v", "", "Enable verbose mode"),
+            (
+                "--output",
+                "FILE",
+                "Write the result to FILE instead of stdout, creating it if needed",
+            ),
+            ("--jobs", "N", "Run N jobs"),
+        ] {
+            let mut names = EcoVec::new();
+            names.push(d2o::types::OptName::from_text(name).unwrap());
+            cmd.options.push(d2o::types::Opt {
+                names,
+                argument: EcoString::from(arg),
+                description: EcoString::from(desc),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
+            });
+        }
+
+        let out = format_native_pretty(&cmd, 50);
+        let rows: Vec<&str> = out
+            .lines()
+            .skip_while(|l| *l != "Options:")
+            .skip(1)
+            .collect();
+        assert_eq!(rows.len(), 4, "{}", out);
+
+        let column = "  --output FILE  ".len();
+        for row in &rows {
+            assert!(row.len() <= 50, "row too wide: {:?}", row);
+            // Every description starts in the same column
+            assert_eq!(&row[column - 1..column], " ", "row: {:?}", row);
+            assert_ne!(&row[column..column + 1], " ", "row: {:?}", row);
+        }
+        assert_eq!(&rows[0][column..], "Enable verbose mode");
+        assert!(rows[1][column..].starts_with("Write the result"));
+        assert!(rows[2][..column].trim().is_empty());
+        assert_eq!(&rows[3][column..], "Run N jobs");
+    }
 
+    #[test]
+    fn test_prepare_for_output_ascii_only() {
+        let mut cmd = Command::new(EcoString::from("test"));
         cmd.options.push(d2o::types::Opt {
             names: {
                 let mut v = EcoVec::new();
                 v.push(d2o::types::OptName::new(
-                    EcoString::from("-v"),
-                    d2o::types::OptNameType::ShortType,
-                ));
-                v.push(d2o::types::OptName::new(
-                    EcoString::from("--verbose"),
+                    EcoString::from("--cafe"),
                     d2o::types::OptNameType::LongType,
                 ));
                 v
             },
-            argument: EcoString::from("FILE"),
-            description: EcoString::from("Enable verbose mode"),
+            argument: EcoString::new(),
+            description: EcoString::from("Order a café"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         });
 
-        cmd.subcommands.push(Command {
-            name: EcoString::from("sub"),
-            description: EcoString::new(),
-            usage: EcoString::new(),
-            options: EcoVec::new(),
-            subcommands: EcoVec::new(),
-            version: EcoString::new(),
-        });
+        let untouched = prepare_for_output(&test_cli(), cmd.clone());
+        assert_eq!(untouched.options[0].description.as_str(), "Order a café");
 
-        let out = format_native(&cmd);
-        assert!(out.contains("Name:  test"));
-        assert!(out.contains("Desc:  Test command"));
-        assert!(out.contains("Usage:\ntest [OPTIONS]"));
-        assert!(out.contains("-v, --verbose"));
-        assert!(out.contains("Subcommand: sub"));
+        let cli = Cli {
+            ascii_only: true,
+            ..test_cli()
+        };
+        let ascii = prepare_for_output(&cli, cmd);
+        assert_eq!(ascii.options[0].description.as_str(), "Order a cafe");
     }
 
     #[tokio::test]
@@ -0,0 +1,230 @@
+use crate::layout::Layout;
+use crate::types::{Opt, OptName};
+use ecow::{EcoString, EcoVec};
+
+/// mdoc macros that may appear inside an `.It` line or a description line
+const MDOC_MACROS: &[&str] = &[
+    "Ad", "An", "Ar", "Cm", "Dq", "Dv", "Em", "Er", "Ev", "Fl", "Ic", "Li", "Nm", "No", "Ns", "Oc",
+    "Oo", "Op", "Pa", "Pq", "Ql", "Qq", "Sq", "Sy", "Va", "Xc", "Xo", "Xr",
+];
+
+/// Parser for BSD mdoc(7) manual page sources.
+///
+/// mdoc marks up options with semantic macros instead of aligned columns:
+///
+/// ```text
+/// .It Fl o Ar file , Fl -output Ns = Ns Ar file
+/// Write output to
+/// .Ar file .
+/// ```
+///
+/// so flags and arguments are read straight from the `.It` line rather than
+/// guessed from indentation.
+pub struct MdocParser;
+
+impl MdocParser {
+    pub fn parse(content: &str) -> EcoVec<Opt> {
+        let lines = Layout::split_lines(content);
+        let mut opts = EcoVec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i].trim();
+            i += 1;
+
+            let Some(item) = line.strip_prefix(".It ") else {
+                continue;
+            };
+            let Some((names, argument)) = Self::parse_item(item) else {
+                continue;
+            };
+
+            // The description runs until the next item or paragraph break
+            let mut description = String::new();
+            while i < lines.len() && !Self::ends_item(lines[i].trim()) {
+                let next = lines[i].trim();
+                i += 1;
+
+                let text = match next.strip_prefix('.') {
+                    Some(macro_line) => Self::render_macro_line(macro_line),
+                    None => Self::unescape(next),
+                };
+                if text.is_empty() {
+                    continue;
+                }
+                if !description.is_empty() {
+                    description.push(' ');
+                }
+                description.push_str(&text);
+            }
+
+            opts.push(Opt {
+                names,
+                argument,
+                description: EcoString::from(description),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
+            });
+        }
+
+        opts
+    }
+
+    /// Check whether the content looks like mdoc source with an option list.
+    pub fn is_mdoc(content: &str) -> bool {
+        content.contains(".It Fl")
+    }
+
+    /// Extract option names and the argument from an `.It` line.
+    fn parse_item(item: &str) -> Option<(EcoVec<OptName>, EcoString)> {
+        let mut tokens = item.split_whitespace().peekable();
+        let mut names = EcoVec::new();
+        let mut argument = EcoString::new();
+        let mut optional = false;
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "Fl" => {
+                    // A bare `Fl` renders as a lone dash
+                    let word = tokens.next_if(|w| !Self::is_macro(w)).unwrap_or_default();
+                    if let Some(name) = OptName::from_text(&format!("-{}", Self::unescape(word))) {
+                        names.push(name);
+                    }
+                }
+                "Ar" => {
+                    // mdoc renders a bare `Ar` as "file ..."
+                    let word = tokens.next_if(|w| !Self::is_macro(w)).unwrap_or("file ...");
+                    if argument.is_empty() {
+                        let arg = Self::unescape(word);
+                        argument = if optional {
+                            EcoString::from(format!("[{}]", arg))
+                        } else {
+                            EcoString::from(arg)
+                        };
+                    }
+                }
+                "Op" | "Oo" => optional = true,
+                "Oc" => optional = false,
+                _ => {}
+            }
+        }
+
+        if names.is_empty() {
+            None
+        } else {
+            Some((names, argument))
+        }
+    }
+
+    #[inline]
+    fn ends_item(line: &str) -> bool {
+        [".It", ".El", ".Bl", ".Sh", ".Ss", ".Pp"]
+            .iter()
+            .any(|m| line == *m || line.starts_with(&format!("{} ", m)))
+    }
+
+    #[inline]
+    fn is_macro(word: &str) -> bool {
+        MDOC_MACROS.contains(&word)
+    }
+
+    /// Render a macro line as plain text, e.g. `Fl v Ns ,` becomes `-v,`.
+    fn render_macro_line(line: &str) -> String {
+        let mut out = String::with_capacity(line.len());
+        let mut no_space = false;
+        let mut flag = false;
+
+        for token in line.split_whitespace() {
+            match token {
+                "Ns" => {
+                    no_space = true;
+                    continue;
+                }
+                "Fl" => {
+                    flag = true;
+                    continue;
+                }
+                t if Self::is_macro(t) => continue,
+                _ => {}
+            }
+
+            let is_punct = matches!(token, "," | "." | ";" | ":" | ")" | "]");
+            if !out.is_empty() && !no_space && !is_punct {
+                out.push(' ');
+            }
+            if flag {
+                out.push('-');
+            }
+            out.push_str(&Self::unescape(token));
+            no_space = false;
+            flag = false;
+        }
+
+        out
+    }
+
+    /// Resolve the roff escapes that commonly appear in option lists.
+    fn unescape(text: &str) -> String {
+        text.replace("\\-", "-")
+            .replace("\\&", "")
+            .replace("\\e", "\\")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LS_MDOC: &str = "\
+.Sh DESCRIPTION
+The following options are available:
+.Bl -tag -width indent
+.It Fl a
+Include directory entries whose names begin with a
+dot
+.Pq Sq \\&. .
+.It Fl C Ar column
+Force multi-column output.
+.It Fl n Op Ar number
+Number the output lines.
+.It Fl o Ar file , Fl -output Ns = Ns Ar file
+Write to
+.Ar file
+instead of stdout; see
+.Fl v .
+.El
+";
+
+    #[test]
+    fn test_parse_mdoc_flags_and_args() {
+        assert!(MdocParser::is_mdoc(LS_MDOC));
+        let opts = MdocParser::parse(LS_MDOC);
+        assert_eq!(opts.len(), 4);
+
+        assert_eq!(opts[0].names[0].raw.as_str(), "-a");
+        assert!(opts[0].argument.is_empty());
+        assert!(opts[0].description.starts_with("Include directory entries"));
+
+        assert_eq!(opts[1].names[0].raw.as_str(), "-C");
+        assert_eq!(opts[1].argument.as_str(), "column");
+        assert_eq!(opts[1].description.as_str(), "Force multi-column output.");
+
+        assert_eq!(opts[2].argument.as_str(), "[number]");
+
+        let names: Vec<&str> = opts[3].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["-o", "--output"]);
+        assert_eq!(opts[3].argument.as_str(), "file");
+        assert_eq!(
+            opts[3].description.as_str(),
+            "Write to file instead of stdout; see -v."
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_non_flag_items() {
+        let content = ".Bl -tag\n.It Pa /etc/motd\nMessage of the day.\n.El\n";
+        assert!(MdocParser::parse(content).is_empty());
+    }
+}
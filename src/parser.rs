@@ -1,15 +1,22 @@
-use crate::types::{Opt, OptName};
-use bstr::ByteSlice;
+use crate::layout::Layout;
+use crate::types::{Opt, OptName, OptNameType};
 use ecow::{EcoString, EcoVec};
 use memchr::memchr;
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashSet;
 
 pub struct Parser;
 
 impl Parser {
     pub fn parse_line(s: &str) -> EcoVec<Opt> {
-        let pairs = Self::preprocess(s);
+        Self::parse_line_with(s, false)
+    }
+
+    /// Like [`Parser::parse_line`], also reading DOS/Windows-style `/flag`
+    /// lines as options when `slash_flags` is set.
+    pub fn parse_line_with(s: &str, slash_flags: bool) -> EcoVec<Opt> {
+        let pairs = Self::preprocess_with(s, slash_flags);
         let mut opts = EcoVec::new();
         let mut seen: HashSet<Opt, foldhash::fast::RandomState> =
             HashSet::with_capacity_and_hasher(pairs.len(), foldhash::fast::RandomState::default());
@@ -25,12 +32,14 @@ impl Parser {
     }
 
     pub fn preprocess(s: &str) -> EcoVec<(EcoString, EcoString)> {
-        // Use bstr for fast line iteration via memchr
-        let bytes = s.as_bytes();
-        let lines: Vec<&str> = bytes
-            .lines()
-            .filter_map(|line| std::str::from_utf8(line).ok())
-            .collect();
+        Self::preprocess_with(s, false)
+    }
+
+    /// Like [`Parser::preprocess`], also pairing `/flag` lines when
+    /// `slash_flags` is set.
+    pub fn preprocess_with(s: &str, slash_flags: bool) -> EcoVec<(EcoString, EcoString)> {
+        let s = Layout::strip_skipped_sections(s);
+        let lines = Layout::split_lines(&s);
         let mut result = EcoVec::new();
         let mut i = 0;
 
@@ -38,26 +47,50 @@ impl Parser {
             let line = lines[i];
             let trimmed = line.trim_start();
 
-            // Fast path: skip lines that don't start with '-' using byte check
+            // Fast path: skip lines that don't start with '-' (or a `/flag`)
+            // using byte check
             let trimmed_bytes = trimmed.as_bytes();
-            if trimmed_bytes.is_empty() || trimmed_bytes[0] != b'-' {
+            if trimmed_bytes.is_empty()
+                || (trimmed_bytes[0] != b'-'
+                    && !(slash_flags && Self::starts_with_slash_flag(trimmed))
+                    && !Self::starts_with_toggle(trimmed))
+            {
                 i += 1;
                 continue;
             }
 
+            // Column layout: a run of two or more spaces (e.g. an expanded tab)
+            // separates the option part from its description
+            if let Some(gap) = trimmed.find("  ") {
+                let desc_part = trimmed[gap..].trim_start();
+                if !desc_part.is_empty() && !desc_part.starts_with('-') {
+                    let mut desc = Self::join_words(desc_part);
+                    i = Self::join_continuation(&lines, i + 1, &mut desc, slash_flags);
+                    result.push((Self::join_words(&trimmed[..gap]), desc));
+                    continue;
+                }
+            }
+
             // Try to split option and description from the same line first
             // Most help text has format: "  -v, --verbose         description text"
-            // Count parts and find opt_end without allocating Vec
+            // Count parts and find opt_end without allocating Vec. After the
+            // last dash-prefixed token, one more token belongs to the option
+            // part only if it looks like an argument (`FILE`, `<file>`) or
+            // ends the line, as in `-f file` with the description below;
+            // anything else starts the description.
+            let part_count = trimmed.split_whitespace().count();
             let mut opt_end = 0;
-            let mut part_count = 0;
+            let mut took_argument = false;
             for (idx, part) in trimmed.split_whitespace().enumerate() {
-                part_count += 1;
                 let part_bytes = part.as_bytes();
                 if part_bytes.first() == Some(&b'-') || idx == 0 {
                     opt_end = idx + 1;
-                } else if memchr(b'=', part_bytes).is_some() || part_bytes.first() != Some(&b'-') {
-                    // Could be an argument marker
+                    took_argument = false;
+                } else if !took_argument
+                    && (idx + 1 == part_count || Self::looks_like_argument(part))
+                {
                     opt_end = idx + 1;
+                    took_argument = true;
                 } else {
                     break;
                 }
@@ -80,8 +113,8 @@ impl Parser {
                         desc_str.push_str(part);
                     }
                 }
+                i = Self::join_continuation(&lines, i + 1, &mut desc_str, slash_flags);
                 result.push((opt_str, desc_str));
-                i += 1;
             } else if opt_end > 0 {
                 // No description on this line, try next line
                 let opt_str = EcoString::from(trimmed);
@@ -89,7 +122,8 @@ impl Parser {
                     let next_trimmed = lines[i + 1].trim_start();
                     let next_bytes = next_trimmed.as_bytes();
                     if !next_bytes.is_empty() && next_bytes[0] != b'-' {
-                        EcoString::from(lines[i + 1].trim())
+                        // Right-aligned text can carry wide internal gaps
+                        Self::join_words(next_trimmed)
                     } else {
                         EcoString::new()
                     }
@@ -98,8 +132,9 @@ impl Parser {
                 };
 
                 if !desc_str.is_empty() {
+                    let mut desc_str = desc_str;
+                    i = Self::join_continuation(&lines, i + 2, &mut desc_str, slash_flags);
                     result.push((opt_str, desc_str));
-                    i += 2;
                 } else {
                     result.push((opt_str, EcoString::new()));
                     i += 1;
@@ -112,36 +147,348 @@ impl Parser {
         result
     }
 
+    /// An argument placeholder following an option name: bracketed
+    /// (`<file>`, `[N]`, `{a,b}`) or ALL-CAPS (`OUT`, `FILE...`).
+    fn looks_like_argument(part: &str) -> bool {
+        if part.starts_with(['<', '[', '{', '(']) || memchr(b'=', part.as_bytes()).is_some() {
+            return true;
+        }
+        let mut letters = part.chars().filter(|c| c.is_alphabetic()).peekable();
+        letters.peek().is_some() && letters.all(char::is_uppercase)
+    }
+
+    /// Append the description's continuation lines, starting at `start`, to
+    /// `desc` and return the index of the first line not consumed. A
+    /// continuation is any non-empty line that does not open an option or a
+    /// section, whatever its indentation, so help that indents continuations
+    /// no deeper than the option itself still joins up. A blank line or a
+    /// line with its own column gap (another table row) ends it.
+    fn join_continuation(
+        lines: &[&str],
+        start: usize,
+        desc: &mut EcoString,
+        slash_flags: bool,
+    ) -> usize {
+        let mut i = start;
+        while let Some(line) = lines.get(i) {
+            let trimmed = line.trim();
+            if trimmed.is_empty()
+                || trimmed.contains("  ")
+                || Layout::is_section_start(trimmed)
+                || (slash_flags && Self::starts_with_slash_flag(trimmed))
+                || Self::starts_with_toggle(trimmed)
+            {
+                break;
+            }
+            for word in trimmed.split_whitespace() {
+                if !desc.is_empty() {
+                    desc.push(' ');
+                }
+                desc.push_str(word);
+            }
+            i += 1;
+        }
+        i
+    }
+
+    /// Check whether a line opens with a DOS/Windows-style `/flag`,
+    /// optionally followed by a `:value` suffix.
+    #[inline]
+    pub(crate) fn starts_with_slash_flag(s: &str) -> bool {
+        let first = s.split_whitespace().next().unwrap_or_default();
+        let first = first.trim_end_matches(',');
+        let name = first.split_once(':').map_or(first, |(name, _)| name);
+        OptName::is_slash_flag(name)
+    }
+
+    /// Join whitespace-separated words with single spaces.
+    fn join_words(s: &str) -> EcoString {
+        let mut joined = EcoString::new();
+        for word in s.split_whitespace() {
+            if !joined.is_empty() {
+                joined.push(' ');
+            }
+            joined.push_str(word);
+        }
+        joined
+    }
+
     pub fn parse_with_opt_part(opt_str: &str, desc_str: &str) -> EcoVec<Opt> {
-        let names = Self::parse_opt_names(opt_str);
-        let arg = Self::parse_opt_arg(opt_str);
+        let written = opt_str;
+        let opt_str = Self::expand_toggle_notation(opt_str);
+        let opt_str = Self::expand_negatable_notation(&opt_str);
+        let mut names = Self::parse_opt_names(&opt_str);
+        let (full_arg, optional_argument) = match Self::parse_equals_arg(&opt_str) {
+            Some((arg, optional)) => (EcoString::from(arg), optional),
+            None => (Self::parse_opt_arg(&opt_str), false),
+        };
+        let (arg, trailing) = Self::split_caps_argument(&full_arg);
+        let (arg, multiple) = Self::split_repetition(arg);
+        // Without a column gap the description may follow an ALL CAPS argument
+        let desc_str = if desc_str.is_empty() {
+            trailing
+        } else {
+            desc_str
+        };
 
         if names.is_empty() {
             return EcoVec::new();
         }
 
+        // `+name` alongside `-name` turns one feature on or off
+        let toggle = names.iter().any(|plus| {
+            plus.opt_type == OptNameType::PlusType
+                && names
+                    .iter()
+                    .any(|minus| minus.raw.strip_prefix('-') == plus.raw.strip_prefix('+'))
+        });
+
         let mut result = EcoVec::new();
+        // `--[no-]name=ARG` takes its argument only in the positive form
+        if !arg.is_empty() {
+            let (negated, positive): (EcoVec<OptName>, EcoVec<OptName>) =
+                names.iter().cloned().partition(|name| {
+                    name.raw.strip_prefix("--no-").is_some_and(|rest| {
+                        written.contains(&format!("{}{}", Self::NEGATABLE_MARKER, rest))
+                    })
+                });
+            if !negated.is_empty() && !positive.is_empty() {
+                result.push(Opt {
+                    names: negated,
+                    argument: EcoString::new(),
+                    description: EcoString::from(desc_str),
+                    multiple: false,
+                    toggle: false,
+                    optional_argument: false,
+                    value_range: None,
+                });
+                names = positive;
+            }
+        }
         result.push(Opt {
             names,
-            argument: arg,
             description: EcoString::from(desc_str),
+            multiple,
+            toggle,
+            optional_argument,
+            value_range: Self::parse_value_range(&arg),
+            argument: arg,
         });
         result
     }
 
+    /// Read numeric bounds from an argument written as a range, such as
+    /// `1-5`, `<0..100>` or `-10..10`.
+    fn parse_value_range(arg: &str) -> Option<(EcoString, EcoString)> {
+        let inner = arg.trim_start_matches(['<', '[', '{']);
+        let inner = inner.trim_end_matches(['>', ']', '}']);
+        let (low, high) = inner.split_once("..").or_else(|| {
+            // Skip a leading minus so `-5-5` splits after the first number
+            let sep = inner.get(1..)?.find('-')? + 1;
+            Some((&inner[..sep], &inner[sep + 1..]))
+        })?;
+
+        let is_number = |s: &str| s.parse::<i64>().is_ok();
+        (is_number(low) && is_number(high)).then(|| (EcoString::from(low), EcoString::from(high)))
+    }
+
+    /// Markers that document both halves of a toggle in one word
+    const TOGGLE_MARKERS: &[&str] = &["[+|-]", "[-|+]", "[+-]", "[-+]", "+/-", "-/+"];
+
+    /// Expand `[+|-]name` into `+name, -name` so both halves are parsed.
+    fn expand_toggle_notation(s: &str) -> Cow<'_, str> {
+        if !s.contains('+') {
+            return Cow::Borrowed(s);
+        }
+
+        let mut changed = false;
+        let words: Vec<Cow<'_, str>> = s
+            .split_whitespace()
+            .map(|word| {
+                let name = Self::TOGGLE_MARKERS
+                    .iter()
+                    .find_map(|marker| word.strip_prefix(marker))
+                    .filter(|name| name.starts_with(|c: char| c.is_ascii_alphabetic()));
+                match name {
+                    Some(name) => {
+                        changed = true;
+                        Cow::Owned(format!("+{}, -{}", name, name))
+                    }
+                    None => Cow::Borrowed(word),
+                }
+            })
+            .collect();
+
+        if changed {
+            Cow::Owned(words.join(" "))
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    /// Marker of a long flag documented with its `--no-` form in one word
+    const NEGATABLE_MARKER: &str = "--[no-]";
+
+    /// Expand `--[no-]name` into `--no-name, --name` so both spellings of a
+    /// negatable flag are parsed.
+    fn expand_negatable_notation(s: &str) -> Cow<'_, str> {
+        const MARKER: &str = Parser::NEGATABLE_MARKER;
+        if !s.contains(MARKER) {
+            return Cow::Borrowed(s);
+        }
+
+        let words: Vec<Cow<'_, str>> = s
+            .split_whitespace()
+            .map(|word| match word.strip_prefix(MARKER) {
+                Some(rest) if rest.starts_with(|c: char| c.is_ascii_alphanumeric()) => {
+                    let name_len = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                        .unwrap_or(rest.len());
+                    Cow::Owned(format!("--no-{}, --{}", &rest[..name_len], rest))
+                }
+                _ => Cow::Borrowed(word),
+            })
+            .collect();
+
+        Cow::Owned(words.join(" "))
+    }
+
+    /// Rewrite PowerShell-style `-Name:<Type>` parameters at the start of
+    /// option lines as `-Name <Type>`, so the type is parsed as the argument.
+    /// Only capitalized single-dash names are split, leaving GNU-style
+    /// `-o:value` words alone; callers opt in with `--input-format powershell`.
+    pub fn split_powershell_params(content: &str) -> EcoString {
+        let mut out = String::with_capacity(content.len() + 16);
+        for line in Layout::split_lines(content) {
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            let first = trimmed.split_whitespace().next().unwrap_or_default();
+            let split = first
+                .strip_prefix('-')
+                .filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+                .and_then(|rest| rest.split_once(':'))
+                .filter(|(name, value)| {
+                    !value.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric())
+                });
+
+            match split {
+                Some((name, value)) => {
+                    out.push_str(indent);
+                    out.push('-');
+                    out.push_str(name);
+                    out.push(' ');
+                    out.push_str(value);
+                    out.push_str(&trimmed[first.len()..]);
+                }
+                None => out.push_str(line),
+            }
+            out.push('\n');
+        }
+        EcoString::from(out)
+    }
+
+    /// Check whether a line opens with a `+name` toggle or `[+|-]name`.
+    #[inline]
+    pub(crate) fn starts_with_toggle(s: &str) -> bool {
+        let first = s.split_whitespace().next().unwrap_or_default();
+        let first = first.trim_end_matches(',');
+        OptName::is_plus_flag(first)
+            || Self::TOGGLE_MARKERS
+                .iter()
+                .any(|marker| first.len() > marker.len() && first.starts_with(marker))
+    }
+
+    /// Find an argument attached with `=`: `--color=WHEN` is required and
+    /// `--color[=WHEN]` optional. Returns the argument and whether it is
+    /// optional.
+    fn parse_equals_arg(s: &str) -> Option<(&str, bool)> {
+        s.split_whitespace()
+            .map(|word| word.trim_end_matches(','))
+            .filter(|word| word.starts_with('-'))
+            .find_map(|word| {
+                if let Some((_, rest)) = word.split_once("[=") {
+                    let arg = rest.strip_suffix(']').unwrap_or(rest);
+                    (!arg.is_empty()).then_some((arg, true))
+                } else {
+                    let (_, arg) = word.split_once('=')?;
+                    (!arg.is_empty()).then_some((arg, false))
+                }
+            })
+    }
+
+    /// Drop an attached `=VALUE` or `[=VALUE]` from an option name.
+    #[inline]
+    fn strip_attached_arg(word: &str) -> &str {
+        match word.find("[=").or_else(|| word.find('=')) {
+            Some(pos) if pos > 1 => &word[..pos],
+            _ => word,
+        }
+    }
+
+    /// Split an ALL CAPS argument from lowercase words that follow it, so
+    /// `FAST run quickly` becomes `FAST` and `run quickly`. Arguments that do
+    /// not start with a caps or bracketed word are kept whole.
+    fn split_caps_argument(arg: &str) -> (&str, &str) {
+        let is_arg_word = |word: &str| {
+            word.starts_with(['<', '[', '{'])
+                || (word.chars().any(|c| c.is_ascii_uppercase())
+                    && !word.chars().any(|c| c.is_lowercase()))
+        };
+
+        // Arguments come from `extract_arg_from_part`, joined by single spaces
+        let count = arg.split(' ').take_while(|word| is_arg_word(word)).count();
+        if count == 0 {
+            return (arg, "");
+        }
+        match arg.match_indices(' ').nth(count - 1) {
+            Some((end, _)) => (&arg[..end], &arg[end + 1..]),
+            None => (arg, ""),
+        }
+    }
+
+    /// Split a trailing `...` repetition marker off an argument, so that
+    /// `<file>...` and `file...` both become `file` with `multiple` set.
+    fn split_repetition(arg: &str) -> (EcoString, bool) {
+        let Some(base) = arg.strip_suffix("...").or_else(|| arg.strip_suffix('…')) else {
+            return (EcoString::from(arg), false);
+        };
+
+        let base = base.trim_end();
+        let base = base
+            .strip_prefix('<')
+            .and_then(|b| b.strip_suffix('>'))
+            .unwrap_or(base);
+
+        if base.is_empty() {
+            return (EcoString::from(arg), false);
+        }
+        (EcoString::from(base), true)
+    }
+
     fn parse_opt_names(s: &str) -> EcoVec<OptName> {
         let mut names = EcoVec::new();
         let mut seen: HashSet<EcoString, foldhash::fast::RandomState> =
             HashSet::with_hasher(foldhash::fast::RandomState::default());
 
-        for part in s.split([',', '/', '|']) {
+        // `/` separates alternatives (`-v/--verbose`) unless it introduces
+        // the names themselves (`/verbose`, `/out:<file>`)
+        let slash = Self::starts_with_slash_flag(s);
+        let separators: &[char] = if slash { &[',', '|'] } else { &[',', '/', '|'] };
+
+        for part in s.split(separators) {
             let trimmed = part.trim();
             if trimmed.is_empty() {
                 continue;
             }
 
             for word in trimmed.split_whitespace() {
-                if word.starts_with('-')
+                let word = if slash {
+                    word.split_once(':').map_or(word, |(name, _)| name)
+                } else {
+                    word
+                };
+                let word = Self::strip_attached_arg(word);
+                if (word.starts_with(['-', '+']) || slash)
                     && let Some(name) = OptName::from_text(word)
                 {
                     // Only add if not already seen (deduplicate)
@@ -158,6 +505,21 @@ impl Parser {
     }
 
     fn parse_opt_arg(s: &str) -> EcoString {
+        if Self::starts_with_slash_flag(s) {
+            for part in s.split([',', '|']) {
+                let trimmed = part.trim();
+                if let Some((_, arg)) = trimmed.split_once(':')
+                    && !arg.is_empty()
+                {
+                    return EcoString::from(arg);
+                }
+                if let Some(arg) = Self::extract_arg_from_part(trimmed) {
+                    return arg;
+                }
+            }
+            return EcoString::new();
+        }
+
         for part in s.split([',', '/', '|']) {
             let trimmed = part.trim();
             if let Some(arg) = Self::extract_arg_from_part(trimmed)
@@ -218,14 +580,42 @@ mod tests {
         let input = "  -a, --all  show all\n  -b\n    show b";
         let pairs = Parser::preprocess(input);
         assert_eq!(pairs.len(), 2);
-        // Current implementation keeps the entire first line as the option
-        // part when it cannot separate a description on the same line.
-        assert_eq!(pairs[0].0.as_str(), "-a, --all  show all");
-        assert_eq!(pairs[0].1.as_str(), "");
+        assert_eq!(pairs[0].0.as_str(), "-a, --all");
+        assert_eq!(pairs[0].1.as_str(), "show all");
         assert_eq!(pairs[1].0.as_str(), "-b");
         assert_eq!(pairs[1].1.as_str(), "show b");
     }
 
+    #[test]
+    fn test_preprocess_single_space_splits_at_description_word() {
+        // Without a column gap, a lowercase word after the option names
+        // starts the description.
+        let pairs = Parser::preprocess("-a, --all show all");
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0.as_str(), "-a, --all");
+        assert_eq!(pairs[0].1.as_str(), "show all");
+    }
+
+    #[test]
+    fn test_preprocess_argument_only_if_uppercase_or_bracketed() {
+        let pairs = Parser::preprocess("-o OUT desc here");
+        assert_eq!(pairs[0].0.as_str(), "-o OUT");
+        assert_eq!(pairs[0].1.as_str(), "desc here");
+
+        let pairs = Parser::preprocess("-o does something");
+        assert_eq!(pairs[0].0.as_str(), "-o");
+        assert_eq!(pairs[0].1.as_str(), "does something");
+
+        let pairs = Parser::preprocess("-o <file> write to file");
+        assert_eq!(pairs[0].0.as_str(), "-o <file>");
+        assert_eq!(pairs[0].1.as_str(), "write to file");
+
+        // Only one argument token is taken; a second caps word is description
+        let pairs = Parser::preprocess("-o OUT FILE is written");
+        assert_eq!(pairs[0].0.as_str(), "-o OUT");
+        assert_eq!(pairs[0].1.as_str(), "FILE is written");
+    }
+
     #[test]
     fn test_parse_usage_header_matches_keywords() {
         let block = "Usage:\n  cmd [OPTIONS]\n";
@@ -233,6 +623,83 @@ mod tests {
         assert!(header.contains("usage"));
     }
 
+    #[test]
+    fn test_parse_powershell_typed_parameter() {
+        let opts = Parser::parse_line("  -Path <String>      Specifies a path.");
+        assert_eq!(opts.len(), 1);
+        assert_eq!(opts[0].names[0].raw.as_str(), "-Path");
+        assert_eq!(opts[0].names[0].opt_type, OptNameType::OldType);
+        assert_eq!(opts[0].argument.as_str(), "<String>");
+        assert_eq!(opts[0].description.as_str(), "Specifies a path.");
+    }
+
+    #[test]
+    fn test_parse_slash_flags() {
+        let content = "  /verbose       Show details.\n  /out:<file>    Write to file.\n  /usr/bin       Not a flag.";
+        // Only read when asked for, so paths in other help stay text
+        assert!(Parser::parse_line(content).is_empty());
+
+        let opts = Parser::parse_line_with(content, true);
+        assert_eq!(opts.len(), 2);
+        assert_eq!(opts[0].names[0].raw.as_str(), "/verbose");
+        assert_eq!(opts[0].names[0].opt_type, OptNameType::SlashType);
+        assert!(opts[0].argument.is_empty());
+        assert_eq!(opts[1].names[0].raw.as_str(), "/out");
+        assert_eq!(opts[1].argument.as_str(), "<file>");
+        assert_eq!(opts[1].description.as_str(), "Write to file.");
+    }
+
+    #[test]
+    fn test_parse_plus_minus_toggle() {
+        let opts = Parser::parse_line("  +color / -color      Enable or disable color");
+        assert_eq!(opts.len(), 1);
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["+color", "-color"]);
+        assert_eq!(opts[0].names[0].opt_type, OptNameType::PlusType);
+        assert!(opts[0].toggle);
+        assert!(opts[0].argument.is_empty());
+        assert_eq!(opts[0].description.as_str(), "Enable or disable color");
+
+        let opts = Parser::parse_line("  [+|-]pager      Use a pager");
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["+pager", "-pager"]);
+        assert!(opts[0].toggle);
+
+        // A lone dash option is not a toggle
+        assert!(!Parser::parse_line("  -color      Colorize")[0].toggle);
+    }
+
+    #[test]
+    fn test_parse_caps_argument_stops_at_description() {
+        let opts = Parser::parse_line("--mode FAST run quickly");
+        assert_eq!(opts.len(), 1);
+        assert_eq!(opts[0].argument.as_str(), "FAST");
+        assert_eq!(opts[0].description.as_str(), "run quickly");
+
+        let opts = Parser::parse_with_opt_part("--level HIGH", "");
+        assert_eq!(opts[0].argument.as_str(), "HIGH");
+        assert!(opts[0].description.is_empty());
+
+        // Lowercase arguments are left alone
+        let opts = Parser::parse_with_opt_part("-o file", "Write output");
+        assert_eq!(opts[0].argument.as_str(), "file");
+        assert_eq!(opts[0].description.as_str(), "Write output");
+    }
+
+    #[test]
+    fn test_parse_optional_equals_argument() {
+        let opts = Parser::parse_with_opt_part("--color[=WHEN]", "colorize output");
+        assert_eq!(opts[0].names[0].raw.as_str(), "--color");
+        assert_eq!(opts[0].argument.as_str(), "WHEN");
+        assert!(opts[0].optional_argument);
+
+        let opts = Parser::parse_with_opt_part("-c, --color=WHEN", "colorize output");
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--color", "-c"]);
+        assert_eq!(opts[0].argument.as_str(), "WHEN");
+        assert!(!opts[0].optional_argument);
+    }
+
     #[test]
     fn test_parse_opt_names() {
         let names = Parser::parse_opt_names("-v, --verbose");
@@ -241,6 +708,183 @@ mod tests {
         assert!(names.iter().any(|n| n.raw.as_str() == "--verbose"));
     }
 
+    #[test]
+    fn test_examples_section_is_not_parsed() {
+        let content = "Options:\n  -q, --quiet      Be quiet\n\nExamples:\n  -v file.txt      Show file.txt verbosely\n  mytool -v -x     Extract verbosely\n\nSEE ALSO\n  -k  other(1)\n";
+        let pairs = Parser::preprocess(content);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0.as_str(), "-q, --quiet");
+
+        let opts = Layout::parse_blockwise(content);
+        assert_eq!(opts.len(), 1);
+        assert!(opts[0].names.iter().any(|n| n.raw == "--quiet"));
+    }
+
+    #[test]
+    fn test_lowercase_argument_with_description_below() {
+        let opts = Parser::parse_line(
+            "  -f file\n      Read input from file.\n  --output path\n      Write to path.",
+        );
+        assert_eq!(opts.len(), 2);
+        assert_eq!(opts[0].names[0].raw.as_str(), "-f");
+        assert_eq!(opts[0].argument.as_str(), "file");
+        assert_eq!(opts[0].description.as_str(), "Read input from file.");
+        assert_eq!(opts[1].argument.as_str(), "path");
+        assert_eq!(opts[1].description.as_str(), "Write to path.");
+    }
+
+    #[test]
+    fn test_examples_section_ends_at_shallower_heading() {
+        // GNU tar after normalization: a column-0 `Examples:`, then sections
+        // at the same one-space indent as the examples and options
+        let content = "Usage: tar [OPTION...] [FILE]...\n\nExamples:\n tar -cf archive.tar foo bar # Create archive.tar\n tar -xf archive.tar # Extract all files\n\n Main operation mode:\n -c, --create create a new archive\n -x, --extract, --get extract files from an archive\n";
+        let opts = Layout::parse_blockwise(content);
+        let names: Vec<&str> = opts.iter().map(|o| o.canonical_name()).collect();
+        assert_eq!(names, ["--create", "--extract"]);
+    }
+
+    #[test]
+    fn test_examples_inside_option_description_are_kept() {
+        // clap/fd long help: `Examples:` belongs to `--exec`'s description
+        let content = "Options:\n  -x, --exec <cmd>\n          Execute a command for each result.\n\n          Examples:\n\n            fd -e zip -x unzip\n\n  -h, --help\n          Print help\n";
+        let opts = Layout::parse_blockwise(content);
+        let names: Vec<&str> = opts.iter().map(|o| o.canonical_name()).collect();
+        assert_eq!(names, ["--exec", "--help"]);
+    }
+
+    #[test]
+    fn test_powershell_colon_parameters() {
+        let content = Parser::split_powershell_params(
+            "  -Path:String          Specifies a path\n  -o:value              GNU style\n",
+        );
+        assert_eq!(
+            content,
+            "  -Path String          Specifies a path\n  -o:value              GNU style\n"
+        );
+
+        let opts = Parser::parse_line(&content);
+        assert_eq!(opts[0].names[0].raw.as_str(), "-Path");
+        assert_eq!(opts[0].names[0].opt_type, OptNameType::OldType);
+        assert_eq!(opts[0].argument.as_str(), "String");
+        assert_eq!(opts[0].description.as_str(), "Specifies a path");
+
+        let content = Parser::split_powershell_params("  -Force:<SwitchParameter>    Overwrite");
+        let opts = Parser::parse_line(&content);
+        assert_eq!(opts[0].names[0].raw.as_str(), "-Force");
+        assert_eq!(opts[0].argument.as_str(), "<SwitchParameter>");
+    }
+
+    #[test]
+    fn test_right_aligned_descriptions_collapse_gaps() {
+        let opts = Parser::parse_line(
+            "  -q, --quiet                              Quiet                      mode",
+        );
+        assert_eq!(opts.len(), 1);
+        assert_eq!(opts[0].description.as_str(), "Quiet mode");
+
+        let opts = Parser::parse_line(
+            "  --level N\n                           Set the level                  [default: 3]",
+        );
+        assert_eq!(opts.len(), 1);
+        assert_eq!(opts[0].argument.as_str(), "N");
+        assert_eq!(opts[0].description.as_str(), "Set the level [default: 3]");
+    }
+
+    #[test]
+    fn test_parse_value_ranges() {
+        let opts = Parser::parse_line(
+            "  --level 1-5      Set the level\n  --pct 0..100      Percentage\n  --name NAME      Plain argument",
+        );
+        assert_eq!(opts.len(), 3);
+        assert_eq!(opts[0].argument.as_str(), "1-5");
+        assert_eq!(
+            opts[0].value_range,
+            Some((EcoString::from("1"), EcoString::from("5")))
+        );
+        assert_eq!(
+            opts[1].value_range,
+            Some((EcoString::from("0"), EcoString::from("100")))
+        );
+        assert_eq!(opts[2].value_range, None);
+
+        assert_eq!(
+            Parser::parse_value_range("<-10..10>"),
+            Some((EcoString::from("-10"), EcoString::from("10")))
+        );
+        assert_eq!(Parser::parse_value_range("in-out"), None);
+        assert_eq!(Parser::parse_value_range("1-"), None);
+    }
+
+    #[test]
+    fn test_parse_negatable_long_flag() {
+        let opts = Parser::parse_line(
+            "  --[no-]color      Use colors\n  --[no-]output=FILE      Write to FILE",
+        );
+        assert_eq!(opts.len(), 3);
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--color", "--no-color"]);
+        assert!(opts[0].argument.is_empty());
+        assert_eq!(opts[0].description.as_str(), "Use colors");
+        // Only the positive form takes the argument
+        let names: Vec<&str> = opts[1].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--no-output"]);
+        assert!(opts[1].argument.is_empty());
+        assert_eq!(opts[1].description.as_str(), "Write to FILE");
+        let names: Vec<&str> = opts[2].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--output"]);
+        assert_eq!(opts[2].argument.as_str(), "FILE");
+    }
+
+    #[test]
+    fn test_parse_dedented_continuation() {
+        let opts = Parser::parse_line(
+            "  -b, --brief      be brief\n  and quiet about it\n  -c, --color      colorize\nthe output\n\n  not part of --color",
+        );
+        assert_eq!(opts.len(), 2);
+        assert_eq!(opts[0].description.as_str(), "be brief and quiet about it");
+        assert_eq!(opts[1].description.as_str(), "colorize the output");
+
+        // Section headers and the next option end the description
+        let opts = Parser::parse_line("  -v      verbose\n  -q      quiet\nExamples:\n  d2o -q");
+        assert_eq!(opts[0].description.as_str(), "verbose");
+        assert_eq!(opts[1].description.as_str(), "quiet");
+    }
+
+    #[test]
+    fn test_parse_opt_names_odd_separators() {
+        for opt_part in [
+            "-v,--verbose",
+            "-v / --verbose",
+            "-v/--verbose",
+            "-v,, --verbose",
+            "-v | | --verbose",
+            "-v ,--verbose",
+        ] {
+            let names = Parser::parse_opt_names(opt_part);
+            let raws: Vec<&str> = names.iter().map(|n| n.raw.as_str()).collect();
+            assert_eq!(raws, ["--verbose", "-v"], "opt part {:?}", opt_part);
+
+            let opts = Parser::parse_line(&format!("  {}      Be verbose", opt_part));
+            assert_eq!(opts.len(), 1, "opt part {:?}", opt_part);
+            assert_eq!(opts[0].names.len(), 2, "opt part {:?}", opt_part);
+            assert!(opts[0].argument.is_empty(), "opt part {:?}", opt_part);
+            assert_eq!(opts[0].description.as_str(), "Be verbose");
+        }
+
+        for line in [
+            "  -o,--output FILE      Write to FILE",
+            "  -o / --output FILE      Write to FILE",
+            "  -o,, --output=FILE      Write to FILE",
+            "  -o | | --output <FILE>      Write to FILE",
+        ] {
+            let opts = Parser::parse_line(line);
+            assert_eq!(opts.len(), 1, "line {:?}", line);
+            assert_eq!(opts[0].names.len(), 2, "line {:?}", line);
+            assert!(opts[0].argument.contains("FILE"), "line {:?}", line);
+            assert_eq!(opts[0].description.as_str(), "Write to FILE");
+        }
+    }
+
     #[test]
     fn test_parse_with_opt_part() {
         let opts = Parser::parse_with_opt_part("-v, --verbose", "Enable verbose mode");
@@ -249,6 +893,21 @@ mod tests {
         assert_eq!(opts[0].description.as_str(), "Enable verbose mode");
     }
 
+    #[test]
+    fn test_parse_with_opt_part_repeated_argument() {
+        let opts = Parser::parse_with_opt_part("-I, --include <dir>...", "Add include dir");
+        assert_eq!(opts[0].argument.as_str(), "dir");
+        assert!(opts[0].multiple);
+
+        let opts = Parser::parse_with_opt_part("--define var...", "Define a variable");
+        assert_eq!(opts[0].argument.as_str(), "var");
+        assert!(opts[0].multiple);
+
+        let opts = Parser::parse_with_opt_part("--name <value>", "Set the name");
+        assert_eq!(opts[0].argument.as_str(), "<value>");
+        assert!(!opts[0].multiple);
+    }
+
     #[test]
     fn test_parse_line_deduplicates_options() {
         let input = "  -v, --verbose  verbose\n  -v, --verbose  verbose";
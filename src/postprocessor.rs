@@ -1,20 +1,41 @@
-use crate::types::{Command, Opt, OptName};
+use crate::types::{Command, Opt, OptName, OptNameType};
 use bstr::ByteSlice;
 use ecow::{EcoString, EcoVec};
 use memchr::memchr;
+use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashSet;
 
 pub struct Postprocessor;
 
 impl Postprocessor {
     pub fn fix_command(mut cmd: Command) -> Command {
-        cmd.options = Self::deduplicate_options(cmd.options);
-        cmd.options = Self::filter_invalid_options(cmd.options);
-        cmd.subcommands = cmd.subcommands.into_iter().map(Self::fix_command).collect();
-
+        cmd.for_each_command_mut(&mut |sub| {
+            let options = Self::dedup_names_within_opt(std::mem::take(&mut sub.options));
+            let options = Self::deduplicate_options(options);
+            sub.options = Self::filter_invalid_options(options);
+        });
         cmd
     }
 
+    /// Remove repeated names inside each option, which merging several
+    /// sources can leave behind, keeping the names in the order parsed.
+    pub fn dedup_names_within_opt(options: EcoVec<Opt>) -> EcoVec<Opt> {
+        options
+            .into_iter()
+            .map(|mut opt| {
+                let mut names: EcoVec<OptName> = EcoVec::with_capacity(opt.names.len());
+                for name in opt.names {
+                    if !names.iter().any(|kept| kept.raw == name.raw) {
+                        names.push(name);
+                    }
+                }
+                opt.names = names;
+                opt
+            })
+            .collect()
+    }
+
     fn deduplicate_options(options: EcoVec<Opt>) -> EcoVec<Opt> {
         // Deduplicate based on (names, argument) - description is not part of the key
         let mut seen: HashSet<(EcoVec<OptName>, EcoString), foldhash::fast::RandomState> =
@@ -22,12 +43,18 @@ impl Postprocessor {
                 options.len(),
                 foldhash::fast::RandomState::default(),
             );
-        let mut result = EcoVec::new();
+        let mut result: EcoVec<Opt> = EcoVec::new();
 
         for opt in options.iter() {
             let key = (opt.names.clone(), opt.argument.clone());
             if seen.insert(key) {
                 result.push(opt.clone());
+            } else if let Some(kept) = result
+                .make_mut()
+                .iter_mut()
+                .find(|kept| kept.names == opt.names && kept.argument == opt.argument)
+            {
+                kept.merge_descriptions(opt);
             }
         }
 
@@ -143,6 +170,285 @@ impl Postprocessor {
         EcoString::from(result)
     }
 
+    /// Remove backspace overstrike left in raw man output: bold `x\x08x`
+    /// and underline `_\x08x` both become `x`. Stray backspaces are dropped.
+    pub fn strip_overstrike(text: &str) -> EcoString {
+        if memchr(0x08, text.as_bytes()).is_none() {
+            return EcoString::from(text);
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c == '\x08' {
+                continue;
+            }
+            if chars.peek() != Some(&'\x08') {
+                result.push(c);
+                continue;
+            }
+            chars.next();
+            match chars.next() {
+                // `x\x08_` is underline written the other way round
+                Some('_') if c != '_' => result.push(c),
+                Some(struck) => result.push(struck),
+                None => result.push(c),
+            }
+        }
+
+        EcoString::from(result)
+    }
+
+    /// Replace en and em dashes that open an option token (`\u{2013}v`,
+    /// `\u{2014}verbose`), as left by smart typography in copy-pasted docs,
+    /// with ASCII hyphens: `-` before a single character, `--` before a
+    /// longer name. Dashes used as punctuation are left alone.
+    pub fn unicode_dashes_to_ascii(text: &str) -> EcoString {
+        // Both dashes encode as 0xE2 0x80 0x93/0x94
+        if memchr(0xE2, text.as_bytes()).is_none() {
+            return EcoString::from(text);
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut prev: Option<char> = None;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let at_token_start =
+                prev.is_none_or(|p| p.is_whitespace() || matches!(p, ',' | '|' | '[' | '('));
+            if matches!(c, '\u{2013}' | '\u{2014}')
+                && at_token_start
+                && chars
+                    .peek()
+                    .is_some_and(|(_, next)| next.is_ascii_alphanumeric())
+            {
+                let rest = &text[i + c.len_utf8()..];
+                let name_len = rest
+                    .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))
+                    .unwrap_or(rest.len());
+                result.push_str(if name_len == 1 { "-" } else { "--" });
+            } else {
+                result.push(c);
+            }
+            prev = Some(c);
+        }
+
+        EcoString::from(result)
+    }
+
+    /// Normalize a whole command tree, such as one assembled from several
+    /// JSON sources: subcommands with the same name are merged, text fields
+    /// are trimmed, option names and options are deduplicated, and options
+    /// and subcommands are sorted, so equal trees always come out identical.
+    pub fn normalize_tree(mut cmd: Command) -> Command {
+        // Parents go first, so duplicate subcommands are merged before the
+        // walk reaches their children
+        cmd.for_each_command_mut(&mut Self::normalize_level);
+        cmd
+    }
+
+    /// Normalize one command's own fields, options and subcommand list.
+    fn normalize_level(cmd: &mut Command) {
+        cmd.description = EcoString::from(cmd.description.trim());
+        cmd.usage = EcoString::from(cmd.usage.trim());
+        cmd.version = EcoString::from(cmd.version.trim());
+
+        let mut options: Vec<Opt> = Vec::with_capacity(cmd.options.len());
+        for mut opt in std::mem::take(&mut cmd.options) {
+            let mut names: Vec<OptName> = opt.names.into_iter().collect();
+            names.sort();
+            names.dedup_by(|a, b| a.raw == b.raw);
+            if names.is_empty() {
+                continue;
+            }
+            opt.names = names.into_iter().collect();
+            opt.argument = EcoString::from(opt.argument.trim());
+            opt.description = EcoString::from(opt.description.trim());
+
+            match options.iter_mut().find(|kept| kept.names == opt.names) {
+                Some(kept) => {
+                    kept.merge_descriptions(&opt);
+                    if kept.argument.is_empty() {
+                        kept.argument = opt.argument;
+                    }
+                }
+                None => options.push(opt),
+            }
+        }
+        options.sort_by(|a, b| a.names.cmp(&b.names));
+        cmd.options = options.into_iter().collect();
+
+        let mut subcommands: Vec<Command> = Vec::with_capacity(cmd.subcommands.len());
+        for mut sub in std::mem::take(&mut cmd.subcommands) {
+            sub.name = EcoString::from(sub.name.trim());
+            match subcommands.iter_mut().find(|kept| kept.name == sub.name) {
+                Some(kept) => kept.merge(sub),
+                None => subcommands.push(sub),
+            }
+        }
+        subcommands.sort_by(|a, b| a.name.cmp(&b.name));
+        cmd.subcommands = subcommands.into_iter().collect();
+    }
+
+    /// Fold old-style names such as `-verbose` into the matching long
+    /// option `--verbose` of the same command, throughout the tree. A name
+    /// listed alongside its long form is dropped; an option left without
+    /// names lends its description and argument to the long option.
+    pub fn reclassify_names(mut cmd: Command) -> Command {
+        cmd.for_each_command_mut(&mut |sub| {
+            sub.options = Self::reclassify_options(std::mem::take(&mut sub.options));
+        });
+        cmd
+    }
+
+    fn reclassify_options(options: EcoVec<Opt>) -> EcoVec<Opt> {
+        let longs: HashSet<EcoString> = options
+            .iter()
+            .flat_map(|opt| opt.names.iter())
+            .filter(|name| name.opt_type == OptNameType::LongType)
+            .map(|name| EcoString::from(name.raw.trim_start_matches('-')))
+            .collect();
+        let is_misread_long = |name: &OptName| {
+            name.opt_type == OptNameType::OldType && longs.contains(&name.raw[1..])
+        };
+
+        let mut kept: Vec<Opt> = Vec::with_capacity(options.len());
+        let mut orphans: Vec<(EcoString, Opt)> = Vec::new();
+        for mut opt in options {
+            let Some(misread) = opt.names.iter().find(|name| is_misread_long(name)) else {
+                kept.push(opt);
+                continue;
+            };
+            let body = EcoString::from(&misread.raw[1..]);
+            opt.names = opt
+                .names
+                .iter()
+                .filter(|name| !is_misread_long(name))
+                .cloned()
+                .collect();
+            if opt.names.is_empty() {
+                orphans.push((body, opt));
+            } else {
+                kept.push(opt);
+            }
+        }
+
+        for (body, orphan) in orphans {
+            let target = kept.iter_mut().find(|opt| {
+                opt.names.iter().any(|name| {
+                    name.opt_type == OptNameType::LongType
+                        && name.raw.trim_start_matches('-') == body
+                })
+            });
+            if let Some(target) = target {
+                target.merge_descriptions(&orphan);
+                if target.argument.is_empty() {
+                    target.argument = orphan.argument;
+                }
+            }
+        }
+
+        kept.into_iter().collect()
+    }
+
+    /// Replace every match of `patterns` in the descriptions of the command,
+    /// its options, positionals and subcommands with `***`.
+    pub fn redact(mut cmd: Command, patterns: &[Regex]) -> Command {
+        if patterns.is_empty() {
+            return cmd;
+        }
+
+        let mask = |text: &EcoString| {
+            let mut masked = Cow::Borrowed(text.as_str());
+            for pattern in patterns {
+                if let Cow::Owned(replaced) = pattern.replace_all(&masked, "***") {
+                    masked = Cow::Owned(replaced);
+                }
+            }
+            EcoString::from(masked)
+        };
+
+        cmd.for_each_command_mut(&mut |sub| {
+            sub.description = mask(&sub.description);
+            for positional in sub.positionals.make_mut() {
+                positional.description = mask(&positional.description);
+            }
+        });
+        cmd.for_each_option_mut(|opt| opt.description = mask(&opt.description));
+
+        cmd
+    }
+
+    /// Transliterate descriptions of all options and subcommands to ASCII.
+    pub fn ascii_only(mut cmd: Command) -> Command {
+        cmd.for_each_command_mut(&mut |sub| sub.description = Self::to_ascii(&sub.description));
+        cmd.for_each_option_mut(|opt| opt.description = Self::to_ascii(&opt.description));
+
+        cmd
+    }
+
+    pub fn to_ascii(text: &str) -> EcoString {
+        // Fast path: nothing to transliterate
+        if text.is_ascii() {
+            return EcoString::from(text);
+        }
+
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_ascii() {
+                result.push(c);
+            } else {
+                result.push_str(Self::transliterate_char(c));
+            }
+        }
+
+        EcoString::from(result)
+    }
+
+    /// Closest ASCII spelling of a non-ASCII character; unknown characters are dropped.
+    fn transliterate_char(c: char) -> &'static str {
+        match c {
+            'À'..='Å' => "A",
+            'à'..='å' => "a",
+            'Æ' => "AE",
+            'æ' => "ae",
+            'Ç' => "C",
+            'ç' => "c",
+            'È'..='Ë' => "E",
+            'è'..='ë' => "e",
+            'Ì'..='Ï' => "I",
+            'ì'..='ï' => "i",
+            'Ð' => "D",
+            'ð' => "d",
+            'Ñ' => "N",
+            'ñ' => "n",
+            'Ò'..='Ö' | 'Ø' => "O",
+            'ò'..='ö' | 'ø' => "o",
+            'Ù'..='Ü' => "U",
+            'ù'..='ü' => "u",
+            'Ý' => "Y",
+            'ý' | 'ÿ' => "y",
+            'Þ' => "Th",
+            'þ' => "th",
+            'ß' => "ss",
+            '‘' | '’' | '′' => "'",
+            '“' | '”' | '″' => "\"",
+            '‐' | '‑' | '–' | '−' => "-",
+            '—' => "--",
+            '…' => "...",
+            '→' => "->",
+            '←' => "<-",
+            '•' | '·' => "*",
+            '×' => "x",
+            '©' => "(c)",
+            '®' => "(R)",
+            '™' => "(TM)",
+            c if c.is_whitespace() => " ",
+            _ => "",
+        }
+    }
+
     pub fn convert_tabs_to_spaces(text: &str, spaces: usize) -> EcoString {
         // SIMD fast path: use memchr to check for tabs
         if memchr(b'\t', text.as_bytes()).is_none() {
@@ -156,9 +462,24 @@ impl Postprocessor {
 mod tests {
     use super::*;
     use crate::OptName;
-    use crate::types::OptNameType;
     use ecow::EcoString;
 
+    /// An option with the given names and description and no argument.
+    fn opt(names: &[&str], description: &str) -> Opt {
+        Opt {
+            names: names
+                .iter()
+                .map(|name| OptName::from_text(name).unwrap())
+                .collect(),
+            argument: EcoString::new(),
+            description: EcoString::from(description),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        }
+    }
+
     #[test]
     fn test_deduplicate_options() {
         let mut opts = EcoVec::new();
@@ -170,6 +491,10 @@ mod tests {
             },
             argument: EcoString::new(),
             description: EcoString::from("verbose"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         });
         opts.push(Opt {
             names: {
@@ -179,12 +504,119 @@ mod tests {
             },
             argument: EcoString::new(),
             description: EcoString::from("verbose"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         });
 
         let result = Postprocessor::deduplicate_options(opts);
         assert_eq!(result.len(), 1);
     }
 
+    #[test]
+    fn test_deduplicate_options_keeps_informative_description() {
+        let opts: EcoVec<Opt> = ["verbose", "Print what is being done.", "be loud"]
+            .into_iter()
+            .map(|desc| Opt {
+                names: {
+                    let mut v = EcoVec::new();
+                    v.push(OptName::new(EcoString::from("-v"), OptNameType::ShortType));
+                    v
+                },
+                argument: EcoString::new(),
+                description: EcoString::from(desc),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
+            })
+            .collect();
+
+        let result = Postprocessor::deduplicate_options(opts);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].description.as_str(), "Print what is being done.");
+    }
+
+    #[test]
+    fn test_normalize_tree_merges_and_sorts() {
+        let mut remote_a = Command::new(EcoString::from("remote"));
+        remote_a.options.push(opt(&["-v"], "verbose "));
+        let mut remote_b = Command::new(EcoString::from("remote "));
+        remote_b.description = EcoString::from("Manage remotes");
+        remote_b
+            .options
+            .push(opt(&["-v"], "Be verbose. Show remote URLs."));
+        remote_b
+            .options
+            .push(opt(&["--all", "-a", "--all"], "All remotes"));
+        let log = Command::new(EcoString::from("log"));
+
+        let mut root = Command::new(EcoString::from("git"));
+        root.description = EcoString::from("  the stupid content tracker\n");
+        root.options.push(opt(&["--version"], "Show version"));
+        root.options.push(opt(&["--bare"], "Bare"));
+        root.subcommands.push(remote_a);
+        root.subcommands.push(log);
+        root.subcommands.push(remote_b);
+
+        let normalized = Postprocessor::normalize_tree(root.clone());
+        assert_eq!(
+            normalized.description.as_str(),
+            "the stupid content tracker"
+        );
+        assert_eq!(normalized.options[0].names[0].raw.as_str(), "--bare");
+
+        let names: Vec<&str> = normalized
+            .subcommands
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, ["log", "remote"]);
+
+        let remote = &normalized.subcommands[1];
+        assert_eq!(remote.description.as_str(), "Manage remotes");
+        assert_eq!(remote.options.len(), 2);
+        let all: Vec<&str> = remote.options[0]
+            .names
+            .iter()
+            .map(|n| n.raw.as_str())
+            .collect();
+        assert_eq!(all, ["--all", "-a"]);
+        assert_eq!(
+            remote.options[1].description.as_str(),
+            "Be verbose. Show remote URLs."
+        );
+
+        // Deterministic and idempotent
+        assert_eq!(Postprocessor::normalize_tree(root), normalized);
+        assert_eq!(
+            Postprocessor::normalize_tree(normalized.clone()),
+            normalized
+        );
+    }
+
+    #[test]
+    fn test_dedup_names_within_opt() {
+        let mut opts = EcoVec::new();
+        opts.push(Opt {
+            names: ["--verbose", "-v", "--verbose", "-v"]
+                .iter()
+                .map(|n| OptName::from_text(n).unwrap())
+                .collect(),
+            argument: EcoString::new(),
+            description: EcoString::from("verbose"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+
+        let result = Postprocessor::dedup_names_within_opt(opts);
+        let names: Vec<&str> = result[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--verbose", "-v"]);
+    }
+
     #[test]
     fn test_remove_bullets() {
         let text = "• Item one\n* Item two\n- Item three";
@@ -206,6 +638,128 @@ mod tests {
         assert!(with_spaces.ends_with("    end"));
     }
 
+    #[test]
+    fn test_strip_overstrike() {
+        let text = "  -\x08-v\x08v, -\x08--\x08-v\x08ve\x08er\x08rb\x08bo\x08os\x08se\x08e      Explain _\x08w_\x08h_\x08a_\x08t is done\n  -\x08-o\x08o F\x08_I\x08_L\x08_E\x08_      Write output";
+        let clean = Postprocessor::strip_overstrike(text);
+        assert_eq!(
+            clean.as_str(),
+            "  -v, --verbose      Explain what is done\n  -o FILE      Write output"
+        );
+
+        let opts = crate::Parser::parse_line(&clean);
+        assert_eq!(opts.len(), 2);
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--verbose", "-v"]);
+        assert_eq!(opts[1].names[0].raw.as_str(), "-o");
+        assert_eq!(opts[1].argument.as_str(), "FILE");
+    }
+
+    #[test]
+    fn test_unicode_dashes_to_ascii() {
+        let text = "  \u{2013}v, \u{2014}verbose      Explain what is being done\n  \u{2013}output FILE      Write it \u{2014} or not";
+        let ascii = Postprocessor::unicode_dashes_to_ascii(text);
+        assert_eq!(
+            ascii.as_str(),
+            "  -v, --verbose      Explain what is being done\n  --output FILE      Write it \u{2014} or not"
+        );
+
+        let opts = crate::Parser::parse_line(&ascii);
+        assert_eq!(opts.len(), 2);
+        let names: Vec<&str> = opts[0].names.iter().map(|n| n.raw.as_str()).collect();
+        assert_eq!(names, ["--verbose", "-v"]);
+        assert_eq!(opts[1].names[0].raw.as_str(), "--output");
+    }
+
+    #[test]
+    fn test_reclassify_names_coalesces_old_and_long_forms() {
+        let mut cmd = Command::new(EcoString::from("tool"));
+        cmd.options
+            .push(opt(&["-verbose"], "Print every step as it runs."));
+        cmd.options.push(opt(&["--verbose"], "verbose"));
+        cmd.options.push(opt(&["-quiet", "--quiet"], "Be quiet"));
+        cmd.options.push(opt(&["-display"], "X display"));
+
+        let fixed = Postprocessor::reclassify_names(cmd);
+        assert_eq!(fixed.options.len(), 3);
+        assert_eq!(fixed.options[0].names.len(), 1);
+        assert_eq!(fixed.options[0].names[0].raw.as_str(), "--verbose");
+        assert_eq!(
+            fixed.options[0].description.as_str(),
+            "Print every step as it runs."
+        );
+        assert_eq!(fixed.options[1].names.len(), 1);
+        assert_eq!(fixed.options[1].names[0].raw.as_str(), "--quiet");
+        // Without a long counterpart the old-style name stays as it is
+        assert_eq!(fixed.options[2].names[0].opt_type, OptNameType::OldType);
+    }
+
+    #[test]
+    fn test_redact_masks_nested_descriptions() {
+        let mut sub = Command::new(EcoString::from("login"));
+        sub.options.push(Opt {
+            names: {
+                let mut v = EcoVec::new();
+                v.push(OptName::new(
+                    EcoString::from("--token"),
+                    OptNameType::LongType,
+                ));
+                v
+            },
+            argument: EcoString::new(),
+            description: EcoString::from("Defaults to sk-abc123 from /home/alice/.token"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        });
+        let mut cmd = Command::new(EcoString::from("tool"));
+        cmd.description = EcoString::from("Uses key sk-zzz9");
+        cmd.subcommands.push(sub);
+
+        let patterns = [
+            Regex::new(r"sk-[a-z0-9]+").unwrap(),
+            Regex::new(r"/home/\w+").unwrap(),
+        ];
+        let redacted = Postprocessor::redact(cmd, &patterns);
+        assert_eq!(redacted.description.as_str(), "Uses key ***");
+        assert_eq!(
+            redacted.subcommands[0].options[0].description.as_str(),
+            "Defaults to *** from ***/.token"
+        );
+    }
+
+    #[test]
+    fn test_ascii_only_transliterates_nested_descriptions() {
+        let opt = Opt {
+            names: {
+                let mut v = EcoVec::new();
+                v.push(OptName::new(EcoString::from("-c"), OptNameType::ShortType));
+                v
+            },
+            argument: EcoString::new(),
+            description: EcoString::from("café → bar"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        };
+
+        let mut child = Command::new(EcoString::from("child"));
+        child.options.push(opt.clone());
+        let mut cmd = Command::new(EcoString::from("root"));
+        cmd.options.push(opt);
+        cmd.subcommands.push(child);
+
+        let fixed = Postprocessor::ascii_only(cmd);
+        assert_eq!(fixed.options[0].description.as_str(), "cafe -> bar");
+        assert_eq!(
+            fixed.subcommands[0].options[0].description.as_str(),
+            "cafe -> bar"
+        );
+        assert_eq!(Postprocessor::to_ascii("plain").as_str(), "plain");
+    }
+
     #[test]
     fn test_fix_command_filters_and_deduplicates() {
         let valid_opt = Opt {
@@ -216,12 +770,20 @@ mod tests {
             },
             argument: EcoString::new(),
             description: EcoString::from("verbose"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         };
 
         let invalid_opt = Opt {
             names: EcoVec::new(),
             argument: EcoString::new(),
             description: EcoString::new(),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         };
 
         let cmd = Command {
@@ -235,6 +797,7 @@ mod tests {
                 v.push(invalid_opt);
                 v
             },
+            positionals: EcoVec::new(),
             subcommands: {
                 let mut v = EcoVec::new();
                 v.push(Command {
@@ -246,12 +809,15 @@ mod tests {
                         opts.push(valid_opt.clone());
                         opts
                     },
+                    positionals: EcoVec::new(),
                     subcommands: EcoVec::new(),
                     version: EcoString::new(),
+                    metadata: None,
                 });
                 v
             },
             version: EcoString::new(),
+            metadata: None,
         };
 
         let fixed = Postprocessor::fix_command(cmd);
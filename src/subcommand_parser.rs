@@ -1,36 +1,84 @@
+use crate::layout::Layout;
 use crate::types::Subcommand;
-use bstr::ByteSlice;
 use ecow::{EcoString, EcoVec};
 use std::collections::BTreeSet;
 
 pub struct SubcommandParser;
 
 impl SubcommandParser {
+    /// Collect subcommands from `content`. When the text has command
+    /// section headers (`Commands:`, or groups such as kubectl's `Basic
+    /// Commands (Beginner):` and `Deploy Commands:`), only the lines under
+    /// those headers are read, across every group; otherwise every line is a
+    /// candidate.
     pub fn parse(content: &str) -> EcoVec<Subcommand> {
-        // Use bstr for SIMD-accelerated line iteration
-        let bytes = content.as_bytes();
-        let lines: Vec<&str> = bytes
-            .lines()
-            .filter_map(|line| std::str::from_utf8(line).ok())
-            .collect();
+        let lines = Layout::split_lines(content);
+        let sections = Self::command_sections(&lines);
         let mut subcommands = BTreeSet::new();
 
-        for window in lines.windows(2) {
-            if let Some(subcommand) = Self::parse_line_pair(window[0], window[1]) {
+        if sections.is_empty() {
+            Self::collect(&lines, &mut subcommands);
+        } else {
+            for section in sections {
+                Self::collect(section, &mut subcommands);
+            }
+        }
+
+        subcommands.into_iter().collect()
+    }
+
+    fn collect(lines: &[&str], subcommands: &mut BTreeSet<Subcommand>) {
+        for (i, first) in lines.iter().enumerate() {
+            if let Some(subcommand) = Self::parse_line_pair(first, &lines[i + 1..]) {
                 subcommands.insert(subcommand);
             }
         }
 
-        for line in &lines {
+        for line in lines {
             if let Some(subcommand) = Self::parse_single_line(line) {
                 subcommands.insert(subcommand);
             }
         }
+    }
 
-        subcommands.into_iter().collect()
+    /// The runs of lines below each command section header, each ending at
+    /// the next header of any kind.
+    fn command_sections<'a>(lines: &'a [&'a str]) -> Vec<&'a [&'a str]> {
+        let mut sections = Vec::new();
+        let mut open: Option<usize> = None;
+
+        for (i, line) in lines.iter().enumerate() {
+            let Some(header) = Self::section_header(line) else {
+                continue;
+            };
+            if let Some(start) = open.take() {
+                sections.push(&lines[start..i]);
+            }
+            if header.to_ascii_lowercase().contains("command") {
+                open = Some(i + 1);
+            }
+        }
+        if let Some(start) = open {
+            sections.push(&lines[start..]);
+        }
+
+        sections
+    }
+
+    /// The name of a section header such as `Deploy Commands:`: a line
+    /// ending in a colon that is neither an option nor a table row.
+    fn section_header(line: &str) -> Option<&str> {
+        let name = line.trim().strip_suffix(':')?;
+        if name.is_empty() || name.starts_with('-') || name.contains("  ") || name.contains(':') {
+            return None;
+        }
+        Some(name)
     }
 
-    fn parse_line_pair(first: &str, second: &str) -> Option<Subcommand> {
+    /// A subcommand name on `first` with its description on the following
+    /// lines. Lines indented deeper than the name are joined into the
+    /// description so wrapped text is kept whole.
+    fn parse_line_pair(first: &str, rest: &[&str]) -> Option<Subcommand> {
         let trimmed_first = first.trim();
         let trimmed_bytes = trimmed_first.as_bytes();
 
@@ -39,13 +87,15 @@ impl SubcommandParser {
             return None;
         }
 
-        let first_word = trimmed_first.split_whitespace().next()?;
+        // A name with text after it is a single-line entry, not a pair
+        let mut words = trimmed_first.split_whitespace();
+        let first_word = words.next()?;
 
-        if !Self::is_valid_subcommand_name(first_word) {
+        if words.next().is_some() || !Self::is_valid_subcommand_name(first_word) {
             return None;
         }
 
-        let desc = second.trim();
+        let desc = rest.first()?.trim();
         let desc_bytes = desc.as_bytes();
 
         // Fast path: skip empty or option descriptions
@@ -53,18 +103,28 @@ impl SubcommandParser {
             return None;
         }
 
-        // Use memchr to find newline if present
-        let desc_line = match memchr::memchr(b'\n', desc_bytes) {
-            Some(pos) => &desc[..pos],
-            None => desc,
-        };
+        let mut joined = EcoString::from(desc);
+        let indent = Self::indent(first);
+        for line in &rest[1..] {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('-') || Self::indent(line) <= indent {
+                break;
+            }
+            joined.push(' ');
+            joined.push_str(trimmed);
+        }
 
         Some(Subcommand {
             cmd: EcoString::from(first_word),
-            desc: EcoString::from(desc_line),
+            desc: joined,
         })
     }
 
+    #[inline]
+    fn indent(line: &str) -> usize {
+        line.len() - line.trim_start().len()
+    }
+
     fn parse_single_line(line: &str) -> Option<Subcommand> {
         let trimmed = line.trim();
         let trimmed_bytes = trimmed.as_bytes();
@@ -131,6 +191,35 @@ mod tests {
         assert!(subs.iter().any(|s| s.cmd.as_str() == "build"));
     }
 
+    #[test]
+    fn test_parse_wrapped_subcommand_description() {
+        let content = "Commands:\n  build\n      Compile the project and all of its\n      dependencies into target/\n  run\n      Run a binary";
+        let subs = SubcommandParser::parse(content);
+        let build = subs.iter().find(|s| s.cmd.as_str() == "build").unwrap();
+        assert_eq!(
+            build.desc.as_str(),
+            "Compile the project and all of its dependencies into target/"
+        );
+        let run = subs.iter().find(|s| s.cmd.as_str() == "run").unwrap();
+        assert_eq!(run.desc.as_str(), "Run a binary");
+    }
+
+    #[test]
+    fn test_parse_multiple_command_groups() {
+        let content = "kubectl controls the Kubernetes cluster manager.\n\n\
+            Basic Commands (Beginner):\n  create        Create a resource from a file\n  \
+            expose        Take a replication controller and expose it\n\n\
+            Deploy Commands:\n  rollout       Manage the rollout of a resource\n  \
+            scale         Set a new size for a deployment\n\n\
+            Usage:\n  kubectl [flags] [options]\n\n\
+            Use \"kubectl <command> --help\" for more information about a given command.";
+        let subs = SubcommandParser::parse(content);
+        let names: Vec<&str> = subs.iter().map(|s| s.cmd.as_str()).collect();
+        assert_eq!(names, ["create", "expose", "rollout", "scale"]);
+        let scale = subs.iter().find(|s| s.cmd.as_str() == "scale").unwrap();
+        assert_eq!(scale.desc.as_str(), "Set a new size for a deployment");
+    }
+
     #[test]
     fn test_is_valid_subcommand_name() {
         assert!(SubcommandParser::is_valid_subcommand_name("run"));
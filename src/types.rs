@@ -13,10 +13,25 @@ pub struct Command {
     pub description: EcoString,
     pub usage: EcoString,
     pub options: EcoVec<Opt>,
+    /// Positional arguments, such as `<FILE>`
+    #[serde(default, skip_serializing_if = "EcoVec::is_empty")]
+    pub positionals: EcoVec<Positional>,
     #[serde(default)]
     pub subcommands: EcoVec<Command>,
     #[serde(default)]
     pub version: EcoString,
+    /// Free-form annotations from hand-written JSON, carried through to
+    /// JSON output untouched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// A positional argument of a command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Positional {
+    /// Placeholder as written in the help, such as `<FILE>`
+    pub name: EcoString,
+    pub description: EcoString,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -24,6 +39,18 @@ pub struct Opt {
     pub names: EcoVec<OptName>,
     pub argument: EcoString,
     pub description: EcoString,
+    /// The option may be repeated (documented as `<arg>...`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+    /// The option is a `+name`/`-name` pair that turns a feature on or off
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub toggle: bool,
+    /// The argument may be omitted (documented as `--name[=VALUE]`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional_argument: bool,
+    /// Numeric bounds shown as the argument, such as `1-5` or `0..100`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_range: Option<(EcoString, EcoString)>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
@@ -71,6 +98,10 @@ pub enum OptNameType {
     OldType,
     DoubleDashAlone,
     SingleDashAlone,
+    /// DOS/Windows-style `/flag`
+    SlashType,
+    /// `+feature`, the enabling half of a `+`/`-` toggle
+    PlusType,
 }
 
 impl PartialOrd for OptName {
@@ -111,9 +142,89 @@ impl OptName {
             s if s.starts_with("--") => Some(OptNameType::LongType),
             s if s.starts_with('-') && s.len() == 2 => Some(OptNameType::ShortType),
             s if s.starts_with('-') => Some(OptNameType::OldType),
+            s if Self::is_slash_flag(s) => Some(OptNameType::SlashType),
+            s if Self::is_plus_flag(s) => Some(OptNameType::PlusType),
             _ => None,
         }
     }
+
+    /// Check for a `+feature` toggle name such as `+color`.
+    pub fn is_plus_flag(s: &str) -> bool {
+        s.strip_prefix('+').is_some_and(|rest| {
+            rest.starts_with(|c: char| c.is_ascii_alphabetic())
+                && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    }
+
+    /// Check for a `/flag` name such as `/verbose` or `/?`, rejecting paths
+    /// like `/usr/bin` that merely start with a slash.
+    pub fn is_slash_flag(s: &str) -> bool {
+        let Some(rest) = s.strip_prefix('/') else {
+            return false;
+        };
+        rest == "?"
+            || (rest.starts_with(|c: char| c.is_ascii_alphabetic())
+                && rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+    }
+}
+
+impl Opt {
+    /// Parse a single help line such as `-v, --verbose   Be verbose` into an
+    /// option. Returns `None` when the line describes no option; only the
+    /// first line of `line` is read.
+    ///
+    /// ```
+    /// use d2o::Opt;
+    ///
+    /// let opt = Opt::from_help_line("  -v, --verbose   Be verbose").unwrap();
+    /// assert_eq!(opt.canonical_name(), "--verbose");
+    /// assert_eq!(opt.description.as_str(), "Be verbose");
+    /// assert!(Opt::from_help_line("Usage: tool [OPTIONS]").is_none());
+    /// ```
+    pub fn from_help_line(line: &str) -> Option<Opt> {
+        let line = line.lines().next()?;
+        crate::parser::Parser::parse_line(line).first().cloned()
+    }
+
+    /// The option's raw names joined with `sep`, for example `-v, --verbose`
+    /// with `", "`. Every text format joins names through this.
+    pub fn names_joined(&self, sep: &str) -> String {
+        self.names
+            .iter()
+            .map(|n| n.raw.as_str())
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    /// The name the option is best known by: its first long name, or its
+    /// first name of any kind.
+    pub fn canonical_name(&self) -> &str {
+        self.names
+            .iter()
+            .find(|n| n.opt_type == OptNameType::LongType)
+            .or_else(|| self.names.first())
+            .map_or("", |n| n.raw.as_str())
+    }
+
+    /// Rank a description by how informative it is: complete sentences
+    /// first, then word count, then raw length.
+    pub fn description_score(desc: &str) -> (usize, usize, usize) {
+        let desc = desc.trim();
+        let sentences = desc
+            .split_inclusive(['.', '!', '?'])
+            .filter(|s| s.ends_with(['.', '!', '?']) && s.split_whitespace().count() > 1)
+            .count();
+        (sentences, desc.split_whitespace().count(), desc.len())
+    }
+
+    /// Keep whichever of the two descriptions scores higher. Ties keep the
+    /// current one, so merging is deterministic in input order.
+    pub fn merge_descriptions(&mut self, other: &Opt) {
+        if Self::description_score(&other.description) > Self::description_score(&self.description)
+        {
+            self.description = other.description.clone();
+        }
+    }
 }
 
 impl std::fmt::Display for OptName {
@@ -124,16 +235,12 @@ impl std::fmt::Display for OptName {
 
 impl std::fmt::Display for Opt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let names = self
-            .names
-            .iter()
-            .map(|n| n.raw.to_string())
-            .collect::<Vec<_>>()
-            .join(" ");
         write!(
             f,
             "{}  ::  {}\n{}\n",
-            names, self.argument, self.description
+            self.names_joined(" "),
+            self.argument,
+            self.description
         )
     }
 }
@@ -151,23 +258,354 @@ impl Command {
             description: EcoString::new(),
             usage: EcoString::new(),
             options: EcoVec::new(),
+            positionals: EcoVec::new(),
             subcommands: EcoVec::new(),
             version: EcoString::new(),
+            metadata: None,
         }
     }
 
+    /// Deserialize Command JSON, rejecting fields the schema does not know
+    /// so typos in hand-written files are reported instead of ignored.
+    pub fn from_json_strict(json: &str) -> serde_json::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        check_known_fields(&value)?;
+        serde_json::from_value(value)
+    }
+
     pub fn as_subcommand(&self) -> Subcommand {
         Subcommand {
             cmd: self.name.clone(),
             desc: self.description.clone(),
         }
     }
+
+    /// Look up a nested subcommand by its path of names below this command,
+    /// e.g. `["remote", "add"]` under `git`. An empty path returns `self`.
+    pub fn find_subcommand(&self, path: &[&str]) -> Option<&Command> {
+        path.iter().try_fold(self, |cmd, name| {
+            cmd.subcommands
+                .iter()
+                .find(|sub| sub.name.as_str() == *name)
+        })
+    }
+
+    /// Join a subcommand path into a single identifier usable as a shell
+    /// function name (`git remote add` -> `git_remote_add`). Characters
+    /// outside `[A-Za-z0-9_]` become `_`.
+    pub fn qualified_name<S: AsRef<str>>(path: &[S]) -> EcoString {
+        let mut name = EcoString::new();
+        for (i, part) in path.iter().enumerate() {
+            if i > 0 {
+                name.push('_');
+            }
+            for c in part.as_ref().chars() {
+                name.push(if c.is_ascii_alphanumeric() { c } else { '_' });
+            }
+        }
+        name
+    }
+
+    /// Whether the help lists a bare `--`, meaning everything after it is
+    /// passed through rather than parsed as options.
+    pub fn has_passthrough(&self) -> bool {
+        self.options.iter().any(|opt| {
+            opt.names
+                .iter()
+                .any(|name| name.opt_type == OptNameType::DoubleDashAlone)
+        })
+    }
+
+    /// Number of options on this command and all nested subcommands.
+    pub fn total_option_count(&self) -> usize {
+        self.options.len()
+            + self
+                .subcommands
+                .iter()
+                .map(Command::total_option_count)
+                .sum::<usize>()
+    }
+
+    /// Number of subcommands at every depth below this command.
+    pub fn total_subcommand_count(&self) -> usize {
+        self.subcommands.len()
+            + self
+                .subcommands
+                .iter()
+                .map(Command::total_subcommand_count)
+                .sum::<usize>()
+    }
+
+    /// Call `f` on this command and every nested subcommand, parents first.
+    pub fn for_each_command_mut(&mut self, f: &mut impl FnMut(&mut Command)) {
+        f(self);
+        for sub in self.subcommands.make_mut() {
+            sub.for_each_command_mut(f);
+        }
+    }
+
+    /// Call `f` on every option of this command and its nested subcommands,
+    /// so per-option passes need not repeat the recursive walk.
+    pub fn for_each_option_mut(&mut self, mut f: impl FnMut(&mut Opt)) {
+        self.for_each_command_mut(&mut |cmd| {
+            for opt in cmd.options.make_mut() {
+                f(opt);
+            }
+        });
+    }
+
+    /// Rename every nested subcommand to its qualified name under this
+    /// command, so `git` > `remote` > `add` becomes `git_remote` and
+    /// `git_remote_add`. The root keeps its own name.
+    pub fn rename_subcommands_with_parent(&mut self) {
+        let prefix = Self::qualified_name(&[self.name.as_str()]);
+        Self::rename_children(&mut self.subcommands, &prefix);
+    }
+
+    /// Merge another parse of the same command (for example its man page
+    /// into its `--help`). Empty fields are filled in, options with the same
+    /// names keep the most informative description, and subcommands with the
+    /// same name are merged recursively.
+    pub fn merge(&mut self, other: Command) {
+        if self.description.is_empty() {
+            self.description = other.description;
+        }
+        if self.usage.is_empty() {
+            self.usage = other.usage;
+        }
+        if self.version.is_empty() {
+            self.version = other.version;
+        }
+        if self.positionals.is_empty() {
+            self.positionals = other.positionals;
+        }
+        if self.metadata.is_none() {
+            self.metadata = other.metadata;
+        }
+
+        for opt in other.options {
+            match self
+                .options
+                .make_mut()
+                .iter_mut()
+                .find(|existing| existing.names == opt.names)
+            {
+                Some(existing) => {
+                    existing.merge_descriptions(&opt);
+                    if existing.argument.is_empty() {
+                        existing.argument = opt.argument;
+                    }
+                }
+                None => self.options.push(opt),
+            }
+        }
+
+        for sub in other.subcommands {
+            match self
+                .subcommands
+                .make_mut()
+                .iter_mut()
+                .find(|existing| existing.name == sub.name)
+            {
+                Some(existing) => existing.merge(sub),
+                None => self.subcommands.push(sub),
+            }
+        }
+    }
+
+    /// A copy of this command without subcommands, whose options are the
+    /// union of the options at every depth, for completion targets with no
+    /// notion of subcommands. Options sharing a name are merged into one,
+    /// keeping the most informative description.
+    pub fn flatten_into_options_only(&self) -> Command {
+        let mut flat = Command {
+            options: EcoVec::new(),
+            subcommands: EcoVec::new(),
+            ..self.clone()
+        };
+        self.flatten_options_into(&mut flat.options);
+        flat
+    }
+
+    fn flatten_options_into(&self, options: &mut EcoVec<Opt>) {
+        for opt in self.options.iter() {
+            match options
+                .make_mut()
+                .iter_mut()
+                .find(|existing| existing.names.iter().any(|name| opt.names.contains(name)))
+            {
+                Some(existing) => {
+                    for name in opt.names.iter() {
+                        if !existing.names.contains(name) {
+                            existing.names.push(name.clone());
+                        }
+                    }
+                    existing.merge_descriptions(opt);
+                    if existing.argument.is_empty() {
+                        existing.argument = opt.argument.clone();
+                    }
+                }
+                None => options.push(opt.clone()),
+            }
+        }
+        for sub in self.subcommands.iter() {
+            sub.flatten_options_into(options);
+        }
+    }
+
+    fn rename_children(subcommands: &mut EcoVec<Command>, prefix: &str) {
+        for sub in subcommands.make_mut() {
+            sub.name = Self::qualified_name(&[prefix, sub.name.as_str()]);
+            let prefix = sub.name.clone();
+            Self::rename_children(&mut sub.subcommands, &prefix);
+        }
+    }
+}
+
+/// Reject object keys in Command JSON that the derived `Deserialize` impls
+/// of `Command`, `Opt` and `Positional` would silently ignore. `metadata`
+/// is free-form and not checked.
+fn check_known_fields(command: &serde_json::Value) -> serde_json::Result<()> {
+    let Some(object) = command.as_object() else {
+        // Not an object: deserialization reports the type error
+        return Ok(());
+    };
+    reject_unknown(object, struct_fields::<Command>(), &[])?;
+
+    let items = |key: &str| {
+        object
+            .get(key)
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_object)
+    };
+    for opt in items("options") {
+        // Written by `--format json` for display; derived from `description`
+        // on load, so only accepted
+        reject_unknown(opt, struct_fields::<Opt>(), &["short_description"])?;
+    }
+    for positional in items("positionals") {
+        reject_unknown(positional, struct_fields::<Positional>(), &[])?;
+    }
+    for sub in object
+        .get("subcommands")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        check_known_fields(sub)?;
+    }
+    Ok(())
+}
+
+fn reject_unknown(
+    object: &serde_json::Map<String, serde_json::Value>,
+    fields: &'static [&'static str],
+    also_accepted: &[&str],
+) -> serde_json::Result<()> {
+    match object
+        .keys()
+        .find(|key| !fields.contains(&key.as_str()) && !also_accepted.contains(&key.as_str()))
+    {
+        Some(key) => Err(serde::de::Error::unknown_field(key, fields)),
+        None => Ok(()),
+    }
+}
+
+/// Field names a derived `Deserialize` struct accepts, read by offering it
+/// a deserializer that records the names and then fails.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> serde::Deserializer<'de> for FieldNames<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: serde::de::Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: serde::de::Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("field names recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_strict_json_accepts_json_generator_output() {
+        use crate::generators::GeneratorOptions;
+        use crate::json_gen::JsonGenerator;
+
+        let mut cmd = Command::new(EcoString::from("tool"));
+        cmd.options
+            .push(opt("--verbose", "Print more. Useful for debugging."));
+        let mut sub = Command::new(EcoString::from("run"));
+        sub.options.push(opt("-q", "Quiet"));
+        cmd.subcommands.push(sub);
+
+        for json_typed in [false, true] {
+            let options = GeneratorOptions {
+                json_typed,
+                ..Default::default()
+            };
+            let json = JsonGenerator::generate_with_options(&cmd, &options);
+            let loaded = Command::from_json_strict(&json).expect("strict load of own output");
+            assert_eq!(loaded, cmd);
+        }
+    }
+
+    #[test]
+    fn test_strict_json_rejects_unknown_fields_at_every_level() {
+        let base = r#"{"name":"tool","description":"","usage":"","options":[]"#;
+        let cases = [
+            (r#","verison":"1.0"}"#, "verison"),
+            (
+                r#","positionals":[{"name":"FILE","description":"","optional":true}]}"#,
+                "optional",
+            ),
+            (
+                r#","subcommands":[{"name":"run","description":"","usage":"","options":[{"names":["-q"],"argument":"","description":"","togle":true}]}]}"#,
+                "togle",
+            ),
+        ];
+        for (rest, field) in cases {
+            let json = format!("{}{}", base, rest);
+            let err = Command::from_json_strict(&json).unwrap_err().to_string();
+            assert!(
+                err.contains(&format!("unknown field `{}`", field)),
+                "{}",
+                err
+            );
+            assert!(serde_json::from_str::<Command>(&json).is_ok());
+        }
+
+        let json = format!("{}{}", base, r#","metadata":{"anything":{"goes":1}}}"#);
+        assert!(Command::from_json_strict(&json).is_ok());
+    }
+
     #[test]
     fn test_command_new_and_as_subcommand() {
         let mut cmd = Command::new(EcoString::from("test"));
@@ -179,4 +617,212 @@ mod tests {
         assert_eq!(sub.cmd.as_str(), "test");
         assert_eq!(sub.desc.as_str(), "Test command");
     }
+
+    fn opt(name: &str, desc: &str) -> Opt {
+        let mut names = EcoVec::new();
+        names.push(OptName::from_text(name).unwrap());
+        Opt {
+            names,
+            argument: EcoString::new(),
+            description: EcoString::from(desc),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
+        }
+    }
+
+    #[test]
+    fn test_names_joined() {
+        let mut verbose = opt("-v", "Be verbose");
+        verbose.names.push(OptName::from_text("--verbose").unwrap());
+
+        assert_eq!(verbose.names_joined(", "), "-v, --verbose");
+        assert_eq!(verbose.names_joined(" "), "-v --verbose");
+        assert_eq!(verbose.to_string(), "-v --verbose  ::  \nBe verbose\n");
+    }
+
+    #[test]
+    fn test_merge_descriptions_prefers_most_informative() {
+        let candidates = [
+            "verbose",
+            "Print each file name as it is copied. Implies --progress.",
+            "print more output about what is happening",
+        ];
+
+        // The winner is the same whichever order the candidates arrive in
+        for rotation in 0..candidates.len() {
+            let mut merged = opt("-v", candidates[rotation]);
+            for i in 1..candidates.len() {
+                merged.merge_descriptions(&opt("-v", candidates[(rotation + i) % 3]));
+            }
+            assert_eq!(merged.description.as_str(), candidates[1]);
+        }
+    }
+
+    #[test]
+    fn test_option_and_subcommand_counts() {
+        let mut add = Command::new(EcoString::from("add"));
+        add.options.push(opt("--force", "Force"));
+        let mut commit = Command::new(EcoString::from("commit"));
+        commit.options.push(opt("--amend", "Amend"));
+        commit.options.push(opt("--message", "Message"));
+        let mut remote = Command::new(EcoString::from("remote"));
+        remote.options.push(opt("--verbose", "Verbose"));
+        remote.subcommands.push(add);
+
+        let mut git = Command::new(EcoString::from("git"));
+        git.options.push(opt("--version", "Version"));
+        git.subcommands.push(remote);
+        git.subcommands.push(commit);
+
+        assert_eq!(git.total_option_count(), 5);
+        assert_eq!(git.total_subcommand_count(), 3);
+
+        let leaf = Command::new(EcoString::from("leaf"));
+        assert_eq!(leaf.total_option_count(), 0);
+        assert_eq!(leaf.total_subcommand_count(), 0);
+    }
+
+    #[test]
+    fn test_opt_from_help_line() {
+        let opt = Opt::from_help_line("-v, --verbose   Be verbose").unwrap();
+        assert_eq!(opt.names.len(), 2);
+        assert!(opt.names.iter().any(|n| n.raw.as_str() == "-v"));
+        assert!(opt.names.iter().any(|n| n.raw.as_str() == "--verbose"));
+        assert_eq!(opt.description.as_str(), "Be verbose");
+        assert!(opt.argument.is_empty());
+
+        let opt = Opt::from_help_line("  -o, --output FILE      Write to FILE").unwrap();
+        assert_eq!(opt.argument.as_str(), "FILE");
+
+        assert!(Opt::from_help_line("This line describes nothing").is_none());
+        assert!(Opt::from_help_line("").is_none());
+    }
+
+    #[test]
+    fn test_flatten_into_options_only() {
+        let mut add = Command::new(EcoString::from("add"));
+        add.options.push(opt("--force", "Force"));
+        add.options.push(opt("--help", "Help"));
+        let mut commit = Command::new(EcoString::from("commit"));
+        commit
+            .options
+            .push(opt("--amend", "Amend the previous commit"));
+        commit
+            .options
+            .push(opt("--force", "Allow an empty commit message."));
+
+        let mut git = Command::new(EcoString::from("git"));
+        git.description = EcoString::from("Version control");
+        git.options.push(opt("--help", "Help"));
+        git.subcommands.push(add);
+        git.subcommands.push(commit);
+
+        let flat = git.flatten_into_options_only();
+        assert_eq!(flat.name.as_str(), "git");
+        assert_eq!(flat.description.as_str(), "Version control");
+        assert!(flat.subcommands.is_empty());
+        assert_eq!(flat.options.len(), 3);
+        let names: Vec<&str> = flat.options.iter().map(|o| o.canonical_name()).collect();
+        assert_eq!(names, ["--help", "--force", "--amend"]);
+        assert_eq!(
+            flat.options[1].description.as_str(),
+            "Allow an empty commit message."
+        );
+    }
+
+    #[test]
+    fn test_command_merge() {
+        let mut help = Command::new(EcoString::from("cp"));
+        help.options.push(opt("-v", "verbose"));
+        let mut sub = Command::new(EcoString::from("sub"));
+        sub.options.push(opt("-a", "All."));
+        help.subcommands.push(sub);
+
+        let mut man = Command::new(EcoString::from("cp"));
+        man.description = EcoString::from("copy files");
+        man.options.push(opt("-v", "Explain what is being done."));
+        man.options.push(opt("-f", "Force."));
+        let mut man_sub = Command::new(EcoString::from("sub"));
+        man_sub.options.push(opt("-b", "Both."));
+        man.subcommands.push(man_sub);
+
+        help.merge(man);
+        assert_eq!(help.description.as_str(), "copy files");
+        assert_eq!(help.options.len(), 2);
+        assert_eq!(
+            help.options[0].description.as_str(),
+            "Explain what is being done."
+        );
+        assert_eq!(help.subcommands.len(), 1);
+        assert_eq!(help.subcommands[0].options.len(), 2);
+    }
+
+    #[test]
+    fn test_for_each_option_mut_reaches_nested_subcommands() {
+        let mut add = Command::new(EcoString::from("add"));
+        add.options.push(opt("-f", "force it"));
+        let mut remote = Command::new(EcoString::from("remote"));
+        remote.options.push(opt("-v", "be verbose"));
+        remote.subcommands.push(add);
+        let mut git = Command::new(EcoString::from("git"));
+        git.options.push(opt("-C", "run in dir"));
+        git.subcommands.push(remote);
+
+        git.for_each_option_mut(|opt| {
+            opt.description = opt.description.to_uppercase();
+        });
+
+        assert_eq!(git.options[0].description.as_str(), "RUN IN DIR");
+        let remote = &git.subcommands[0];
+        assert_eq!(remote.options[0].description.as_str(), "BE VERBOSE");
+        assert_eq!(
+            remote.subcommands[0].options[0].description.as_str(),
+            "FORCE IT"
+        );
+    }
+
+    #[test]
+    fn test_find_subcommand() {
+        let mut remote = Command::new(EcoString::from("remote"));
+        remote
+            .subcommands
+            .push(Command::new(EcoString::from("add")));
+        let mut git = Command::new(EcoString::from("git"));
+        git.subcommands.push(remote);
+        git.subcommands.push(Command::new(EcoString::from("log")));
+
+        let add = git.find_subcommand(&["remote", "add"]).expect("remote add");
+        assert_eq!(add.name.as_str(), "add");
+        assert_eq!(git.find_subcommand(&["log"]).unwrap().name.as_str(), "log");
+        assert_eq!(git.find_subcommand(&[]), Some(&git));
+        assert!(git.find_subcommand(&["remote", "rm"]).is_none());
+        assert!(git.find_subcommand(&["log", "add"]).is_none());
+    }
+
+    #[test]
+    fn test_rename_subcommands_with_parent() {
+        let mut add = Command::new(EcoString::from("add"));
+        add.subcommands
+            .push(Command::new(EcoString::from("dry-run")));
+        let mut remote = Command::new(EcoString::from("remote"));
+        remote.subcommands.push(add);
+        let mut git = Command::new(EcoString::from("git"));
+        git.subcommands.push(remote);
+
+        git.rename_subcommands_with_parent();
+        assert_eq!(git.name.as_str(), "git");
+        let remote = &git.subcommands[0];
+        assert_eq!(remote.name.as_str(), "git_remote");
+        assert_eq!(remote.subcommands[0].name.as_str(), "git_remote_add");
+        assert_eq!(
+            remote.subcommands[0].subcommands[0].name.as_str(),
+            "git_remote_add_dry_run"
+        );
+        assert_eq!(
+            Command::qualified_name(&["my tool", "sub"]).as_str(),
+            "my_tool_sub"
+        );
+    }
 }
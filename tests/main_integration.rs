@@ -79,6 +79,88 @@ fn cli_write_caches_to_home_d2o() {
     );
 }
 
+/// --overwrite false must refuse to replace an existing --write target
+#[test]
+fn test_write_overwrite_false_keeps_existing_file() {
+    use std::io::Write;
+
+    let mut help_tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        help_tmp,
+        "USAGE: keepcmd [OPTIONS]\n\nOPTIONS:\n  -v, --verbose  be verbose"
+    )
+    .unwrap();
+    let help_path = help_tmp.path().to_str().unwrap().to_string();
+    let file_name = help_tmp.path().file_name().unwrap().to_str().unwrap();
+
+    let home_dir = tempfile::TempDir::new().expect("create temp home");
+    let existing = home_dir
+        .path()
+        .join(".d2o")
+        .join(format!("{}.bash", file_name));
+    std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+    std::fs::write(&existing, "# hand-edited\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    let assert = cmd
+        .env("HOME", home_dir.path())
+        .env("USERPROFILE", home_dir.path())
+        .args([
+            "--file",
+            &help_path,
+            "--format",
+            "bash",
+            "--cache",
+            "false",
+            "--write",
+            "--overwrite",
+            "false",
+        ])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    assert!(
+        stderr.contains(existing.to_str().unwrap()),
+        "stderr: {}",
+        stderr
+    );
+    assert_eq!(
+        std::fs::read_to_string(&existing).unwrap(),
+        "# hand-edited\n"
+    );
+}
+
+/// --verify runs `bash -n` over the generated bash completion
+#[test]
+fn test_verify_bash_completion_passes_syntax_check() {
+    use std::io::Write;
+
+    if std::process::Command::new("bash")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    let mut help_tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        help_tmp,
+        "Usage: vcmd [OPTIONS]\n\nOptions:\n  -o, --output <FILE>      Write to FILE\n  -v, --verbose      Say what's happening"
+    )
+    .unwrap();
+    let help_path = help_tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args([
+        "--file", &help_path, "--format", "bash", "--cache", "false", "--verify",
+    ])
+    .assert()
+    .success()
+    .stderr(predicate::str::contains("verify: bash syntax OK"));
+}
+
 /// Use the same help text but output JSON and ensure basic fields exist
 #[test]
 fn cli_file_json_output() {
@@ -109,6 +191,44 @@ fn cli_file_json_output() {
     assert!(value["options"].is_array());
 }
 
+/// Tab-aligned help (Go style) keeps option and description apart
+#[test]
+fn cli_file_tab_separated_options() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        tmp,
+        "Usage: gotool [flags]\n\nFlags:\n\t-v\tverbose output\n\t--name string\tset the name"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    let assert = cmd
+        .args(["--file", &path, "--format", "json", "--cache", "false"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&stdout).expect("valid json");
+    let options = value["options"].as_array().expect("options array");
+    assert_eq!(options.len(), 2);
+
+    let verbose = options
+        .iter()
+        .find(|o| o["names"] == serde_json::json!(["-v"]))
+        .expect("-v option");
+    assert_eq!(verbose["description"], "verbose output");
+
+    let name = options
+        .iter()
+        .find(|o| o["names"] == serde_json::json!(["--name"]))
+        .expect("--name option");
+    assert_eq!(name["argument"], "string");
+    assert_eq!(name["description"], "set the name");
+}
+
 /// Ensure completions flag at least runs for bash
 #[test]
 fn cli_completions_bash() {
@@ -119,6 +239,500 @@ fn cli_completions_bash() {
         .stdout(predicate::str::contains("_d2o"));
 }
 
+/// --self-completions is the canonical spelling of --completions
+#[test]
+fn cli_self_completions_fish() {
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args(["--self-completions", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete -c d2o"));
+}
+
+/// --file - reads help text piped on stdin and names the command "command"
+#[test]
+fn cli_file_dash_reads_stdin() {
+    let output = cargo_bin_cmd!("d2o")
+        .args(["-f", "-", "--format", "fish", "--cache", "false"])
+        .write_stdin("Usage: mycmd [OPTIONS]\n\nOptions:\n  -v, --verbose       Verbose output\n")
+        .output()
+        .expect("run d2o");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // `command` is a fish keyword, so the name is quoted
+    assert!(
+        stdout.contains("complete -c 'command' -l 'verbose'"),
+        "{}",
+        stdout
+    );
+}
+
+/// --completions all emits d2o's own completion for every shell in sections
+#[test]
+fn cli_completions_all_has_section_per_shell() {
+    let output = cargo_bin_cmd!("d2o")
+        .args(["--completions", "all"])
+        .output()
+        .expect("run d2o");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let mut last = 0;
+    for shell in ["bash", "zsh", "fish", "powershell", "elvish", "nushell"] {
+        let header = format!("# ===== {} =====", shell);
+        let at = stdout
+            .find(&header)
+            .unwrap_or_else(|| panic!("missing {} section", shell));
+        assert!(at >= last, "{} section out of order", shell);
+        last = at;
+    }
+    assert!(stdout.contains("_d2o"));
+    assert!(stdout.contains("complete -c d2o"));
+    assert!(stdout.contains("Register-ArgumentCompleter"));
+}
+
+/// With an input source, --completions generates the parsed command's script
+#[test]
+fn cli_completions_with_input_source() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        tmp,
+        "USAGE: mycmd [OPTIONS]\n\nOPTIONS:\n  -v, --verbose      be verbose"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+    let file_name = tmp
+        .path()
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args(["--completions", "fish", "--file", &path, "--cache", "false"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(format!("complete -c {} -l 'verbose'", file_name))
+                .and(predicate::str::contains("complete -c d2o").not()),
+        );
+}
+
+/// D2O_FORMAT sets the default output format
+#[test]
+fn cli_format_from_env() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        tmp,
+        "USAGE: mycmd [OPTIONS]\n\nOPTIONS:\n  -v, --verbose      be verbose"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.env("D2O_FORMAT", "json")
+        .args(["--file", &path, "--cache", "false"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{").and(predicate::str::contains("\"--verbose\"")));
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.env("D2O_FORMAT", "json")
+        .args(["--file", &path, "--cache", "false", "--format", "fish"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete -c"));
+}
+
+/// --input-format man parses mdoc macros from man page source
+#[test]
+fn cli_file_mdoc_input_format() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp man page");
+    writeln!(
+        tmp,
+        ".Bl -tag -width Ds\n.It Fl C Ar column\nForce multi-column output.\n.It Fl o Ar file , Fl -output Ns = Ns Ar file\nWrite to a file.\n.El"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args([
+        "--file",
+        &path,
+        "--input-format",
+        "man",
+        "--format",
+        "json",
+        "--cache",
+        "false",
+    ])
+    .assert()
+    .success()
+    .stdout(
+        predicate::str::contains("\"--output\"")
+            .and(predicate::str::contains("\"argument\": \"column\"")),
+    );
+}
+
+/// --header prepends a generated-by comment; default output has none
+#[test]
+fn cli_header_only_with_flag() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        tmp,
+        "USAGE: hdrcmd [OPTIONS]\n\nOPTIONS:\n  -v, --verbose      Enable verbose output"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+    let header = format!("# Generated by d2o {}", env!("CARGO_PKG_VERSION"));
+
+    let mut plain = cargo_bin_cmd!("d2o");
+    plain
+        .args(["--file", &path, "--format", "fish", "--cache", "false"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Generated by d2o").not());
+
+    let mut with_header = cargo_bin_cmd!("d2o");
+    with_header
+        .args([
+            "--file", &path, "--format", "fish", "--cache", "false", "--header",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(header));
+}
+
+/// --install-layout vendor writes each shell's file to its vendor path
+#[test]
+fn cli_install_layout_vendor_writes_under_prefix() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        tmp,
+        "USAGE: pkgcmd [OPTIONS]\n\nOPTIONS:\n  -v, --verbose      Enable verbose output"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+    let prefix = tempfile::TempDir::new().expect("create temp prefix");
+    let name = std::path::Path::new(&path)
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args([
+        "--file",
+        &path,
+        "--cache",
+        "false",
+        "--install-layout",
+        "vendor",
+        "--prefix",
+        prefix.path().to_str().unwrap(),
+    ])
+    .assert()
+    .success();
+
+    let share = prefix.path().join("share");
+    let bash = share.join("bash-completion/completions").join(&name);
+    let zsh = share.join("zsh/site-functions").join(format!("_{}", name));
+    let fish = share
+        .join("fish/vendor_completions.d")
+        .join(format!("{}.fish", name));
+
+    assert!(
+        std::fs::read_to_string(bash)
+            .unwrap()
+            .contains("compgen -W")
+    );
+    assert!(std::fs::read_to_string(zsh).unwrap().contains("#compdef"));
+    assert!(
+        std::fs::read_to_string(fish)
+            .unwrap()
+            .contains("-l 'verbose'")
+    );
+}
+
+/// --input-format markdown parses only fenced help blocks of a README
+#[test]
+fn cli_file_markdown_input_format() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp markdown");
+    writeln!(
+        tmp,
+        "# tool\n\n--prose-flag      is mentioned in prose.\n\n```text\nUsage: tool [OPTIONS]\n\n  -v, --verbose      Print more output\n```"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args([
+        "--file",
+        &path,
+        "--input-format",
+        "markdown",
+        "--format",
+        "json",
+        "--cache",
+        "false",
+    ])
+    .assert()
+    .success()
+    .stdout(
+        predicate::str::contains("\"--verbose\"")
+            .and(predicate::str::contains("--prose-flag").not()),
+    );
+}
+
+/// --seed-cache with --commands-file stores one entry per listed command
+#[cfg(unix)]
+#[test]
+fn cli_seed_cache_from_commands_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::TempDir::new().expect("create temp dir");
+    let cache_home = dir.path().join("cache");
+    let mut list = String::from("# commands to warm\n\n");
+    for name in ["alpha", "beta"] {
+        let script = dir.path().join(name);
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nprintf 'Usage: {name} [OPTIONS]\\n\\nOptions:\\n  -v, --verbose      be verbose\\n'\n"
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        list.push_str(&format!("{}\n", script.display()));
+    }
+    let list_path = dir.path().join("commands.txt");
+    std::fs::write(&list_path, list).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.env("XDG_CACHE_HOME", &cache_home)
+        .args(["--seed-cache", "--skip-man", "--commands-file"])
+        .arg(&list_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Seeded 2 cache entries"));
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.env("XDG_CACHE_HOME", &cache_home)
+        .arg("--cache-stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 entries (2 valid, 0 expired)"));
+}
+
+/// --seed-cache without an input source fails instead of seeding nothing
+#[test]
+fn cli_seed_cache_requires_input() {
+    let dir = tempfile::TempDir::new().expect("create temp dir");
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.env("XDG_CACHE_HOME", dir.path())
+        .arg("--seed-cache")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No input source specified for --seed-cache",
+        ));
+}
+
+/// --command-args fetches help for a nested command level
+#[cfg(unix)]
+#[test]
+fn cli_command_args_fetches_nested_help() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::TempDir::new().expect("create temp dir");
+    let script = dir.path().join("fakectl");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\n\
+         if [ \"$1 $2 $3\" = \"compose up --help\" ]; then\n\
+         printf 'Usage: fakectl compose up\\n\\nOptions:\\n  -d, --detach      Run in background\\n'\n\
+         else\n\
+         printf 'Usage: fakectl\\n\\nOptions:\\n  -q, --quiet      Top level only\\n'\n\
+         fi\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args(["--command"])
+        .arg(&script)
+        .args([
+            "--command-args",
+            "compose up",
+            "--format",
+            "json",
+            "--cache",
+            "false",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--detach"))
+        .stdout(predicate::str::contains("--quiet").not());
+}
+
+/// --lang C makes a locale-aware tool print English headers
+#[cfg(unix)]
+#[test]
+fn cli_lang_forces_child_locale() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::TempDir::new().expect("create temp dir");
+    let script = dir.path().join("localized");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\n\
+         if [ \"$LC_ALL\" = C ]; then\n\
+         printf 'Usage: localized [OPTIONS]\\n\\nOptions:\\n  -v, --verbose      be verbose\\n'\n\
+         else\n\
+         printf 'Verwendung: localized [OPTIONEN]\\n\\nOptionen:\\n  -v, --verbose      ausfuehrlich\\n'\n\
+         fi\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let run = |lang: Option<&str>| {
+        let mut cmd = cargo_bin_cmd!("d2o");
+        cmd.env("LC_ALL", "de_DE.UTF-8")
+            .arg("--command")
+            .arg(&script)
+            .args(["--skip-man", "--format", "json", "--cache", "false"]);
+        if let Some(lang) = lang {
+            cmd.args(["--lang", lang]);
+        }
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    let english = run(Some("C"));
+    assert!(english.contains("Usage: localized"), "{}", english);
+    assert!(english.contains("be verbose"));
+
+    let german = run(None);
+    assert!(german.contains("ausfuehrlich"), "{}", german);
+}
+
+/// Generated bash completion offers no flags after a passthrough `--`
+#[test]
+fn test_bash_completion_stops_after_double_dash() {
+    use std::io::Write;
+
+    if std::process::Command::new("bash")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return;
+    }
+
+    let mut help_tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        help_tmp,
+        "Usage: runner [OPTIONS] [--] [ARGS]...\n\nOptions:\n  -v, --verbose      Say what's happening\n  --      Pass the remaining arguments to the program"
+    )
+    .unwrap();
+    let help_path = help_tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    let assert = cmd
+        .args(["--file", &help_path, "--format", "bash", "--cache", "false"])
+        .assert()
+        .success();
+    let script = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let function = script
+        .lines()
+        .next()
+        .and_then(|line| line.strip_suffix("()"))
+        .expect("completion function")
+        .to_string();
+
+    let complete = |words: &str, cword: usize| {
+        let probe = format!(
+            "{}\nCOMP_WORDS=({}); COMP_CWORD={}; {}; echo \"${{COMPREPLY[*]}}\"",
+            script, words, cword, function
+        );
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(probe)
+            .output()
+            .expect("run bash");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(complete("runner --v", 1).contains("--verbose"));
+    assert_eq!(complete("runner -- --v", 2).trim(), "");
+}
+
+/// --profile prints a timing row for every phase to stderr
+#[test]
+fn test_profile_prints_phase_timings() {
+    use std::io::Write;
+
+    let mut help_tmp = tempfile::NamedTempFile::new().expect("create temp help");
+    writeln!(
+        help_tmp,
+        "Usage: timed [OPTIONS]\n\nOptions:\n  -v, --verbose      be verbose"
+    )
+    .unwrap();
+    let help_path = help_tmp.path().to_str().unwrap().to_string();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    let assert = cmd
+        .args([
+            "--file",
+            &help_path,
+            "--format",
+            "fish",
+            "--cache",
+            "false",
+            "--profile",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("verbose"));
+
+    let stderr = String::from_utf8(assert.get_output().stderr.clone()).unwrap();
+    for phase in ["read", "normalize", "parse", "generate", "total"] {
+        assert!(
+            stderr.lines().any(|line| line.starts_with(phase)),
+            "missing {} in {}",
+            phase,
+            stderr
+        );
+    }
+}
+
+/// A bogus --command fails early with a friendly error
+#[test]
+fn cli_command_not_found() {
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args(["--command", "d2o-no-such-command-xyz", "--cache", "false"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "command not found: d2o-no-such-command-xyz",
+        ));
+}
+
 /// Test --list-subcommands path using a help snippet via --file
 #[test]
 fn cli_list_subcommands_from_file() {
@@ -180,9 +794,15 @@ fn cli_loadjson_native_output() {
             )],
             argument: EcoString::new(),
             description: EcoString::from("Verbose"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let json = serde_json::to_string(&cmd_struct).unwrap();
@@ -196,3 +816,187 @@ fn cli_loadjson_native_output() {
         .success()
         .stdout(predicate::str::contains("Name:  jsoncmd").and(predicate::str::contains("-v (")));
 }
+
+/// Test --merge-json folds partial Command JSONs into the --loadjson one
+#[test]
+fn cli_merge_json_combines_options() {
+    use std::io::Write;
+
+    let partial = |flag: &str, desc: &str| {
+        format!(
+            r#"{{"name":"tool","description":"","usage":"","options":[{{"names":["{}"],"argument":"","description":"{}"}}],"subcommands":[],"version":""}}"#,
+            flag, desc
+        )
+    };
+
+    let mut first = tempfile::NamedTempFile::new().expect("create json temp");
+    write!(first, "{}", partial("--alpha", "First option")).unwrap();
+    let mut second = tempfile::NamedTempFile::new().expect("create json temp");
+    write!(second, "{}", partial("--beta", "Second option")).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("d2o");
+    cmd.args([
+        "--loadjson",
+        first.path().to_str().unwrap(),
+        "--merge-json",
+        second.path().to_str().unwrap(),
+        "--format",
+        "json",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("--alpha").and(predicate::str::contains("--beta")));
+}
+
+/// Test --strict-json rejects misspelled fields that are otherwise ignored
+#[test]
+fn cli_strict_json_rejects_unknown_fields() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create json temp");
+    write!(
+        tmp,
+        r#"{{"name":"tool","description":"","usage":"","options":[{{"names":["--alpha"],"argument":"","description":"Alpha","mulitple":true}}],"subcommands":[],"version":""}}"#
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    cargo_bin_cmd!("d2o")
+        .args(["--loadjson", path, "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--alpha"));
+
+    cargo_bin_cmd!("d2o")
+        .args(["--loadjson", path, "--strict-json", "--format", "json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown field `mulitple`"));
+}
+
+/// Test --json-sorted output is ordered and identical across runs
+#[test]
+fn cli_json_sorted_is_stable() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp");
+    writeln!(
+        tmp,
+        "Usage: tool [OPTIONS]\n\nOptions:\n  -z, --zebra       Zebra mode\n  -a, --alpha       Alpha mode\n  -m                Middle\n"
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let run = || {
+        let output = cargo_bin_cmd!("d2o")
+            .args([
+                "--file",
+                path,
+                "--format",
+                "json",
+                "--json-sorted",
+                "--cache",
+                "false",
+            ])
+            .output()
+            .expect("run d2o");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let first = run();
+    assert_eq!(first, run());
+
+    let alpha = first.find("--alpha").unwrap();
+    let middle = first.find("\"-m\"").unwrap();
+    let zebra = first.find("--zebra").unwrap();
+    assert!(alpha < zebra && zebra < middle);
+}
+
+/// Test --redact masks matches in both the output and the cached entry
+#[test]
+fn cli_redact_masks_output_and_cache() {
+    use std::io::Write;
+
+    fn cached_json(dir: &std::path::Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                cached_json(&path, found);
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                found.push(std::fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+
+    let cache_home = tempfile::TempDir::new().expect("create cache dir");
+    let mut tmp = tempfile::NamedTempFile::new().expect("create temp");
+    writeln!(
+        tmp,
+        "Usage: tool [OPTIONS]\n\nOptions:\n  --token TOKEN      API token (default sk-abc123)\n"
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("d2o")
+        .env("XDG_CACHE_HOME", cache_home.path())
+        .env("HOME", cache_home.path())
+        .args(["--file", tmp.path().to_str().unwrap()])
+        .args(["--format", "json", "--redact", "sk-[a-z0-9]+"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("API token (default ***)")
+                .and(predicate::str::contains("sk-abc123").not()),
+        );
+
+    let mut entries = Vec::new();
+    cached_json(cache_home.path(), &mut entries);
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].contains("API token (default ***)"));
+    assert!(!entries[0].contains("sk-abc123"));
+}
+
+/// Test metadata in loaded JSON survives to JSON and JSON5 output
+#[test]
+fn cli_loadjson_keeps_metadata() {
+    use std::io::Write;
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("create json temp");
+    write!(
+        tmp,
+        r#"{{"name":"tool","description":"","usage":"","options":[],"subcommands":[{{"name":"sub","description":"","usage":"","options":[],"metadata":{{"owner":"sub-team"}}}}],"metadata":{{"_comment":"hand-written","tags":["a","b"],"rev":3}}}}"#
+    )
+    .unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let output = cargo_bin_cmd!("d2o")
+        .args(["--loadjson", path, "--strict-json", "--format", "json"])
+        .output()
+        .expect("run d2o");
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        value["metadata"],
+        serde_json::json!({"_comment": "hand-written", "tags": ["a", "b"], "rev": 3})
+    );
+    assert_eq!(value["subcommands"][0]["metadata"]["owner"], "sub-team");
+
+    let mut json5 = tempfile::NamedTempFile::new().expect("create json5 temp");
+    let output = cargo_bin_cmd!("d2o")
+        .args(["--loadjson", path, "--format", "json5"])
+        .output()
+        .expect("run d2o");
+    json5.write_all(&output.stdout).unwrap();
+
+    let reloaded = cargo_bin_cmd!("d2o")
+        .args([
+            "--loadjson5",
+            json5.path().to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("run d2o");
+    let value: serde_json::Value = serde_json::from_slice(&reloaded.stdout).unwrap();
+    assert_eq!(value["metadata"]["tags"][1], "b");
+}
@@ -71,6 +71,10 @@ fn opt_strategy() -> impl Strategy<Value = Opt> {
             names: names.into_iter().collect::<EcoVec<_>>(),
             argument,
             description,
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         })
 }
 
@@ -86,8 +90,10 @@ fn command_strategy() -> impl Strategy<Value = Command> {
             description: EcoString::from(description),
             usage: EcoString::new(),
             options: options.into_iter().collect::<EcoVec<_>>(),
+            positionals: eco_vec![],
             subcommands: eco_vec![],
             version: EcoString::new(),
+            metadata: None,
         })
 }
 
@@ -336,14 +342,20 @@ proptest! {
             names: eco_vec![OptName::new(EcoString::from("-u"), OptNameType::ShortType)],
             argument: EcoString::new(),
             description: EcoString::from(desc.clone()),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         };
         let cmd = Command {
             name: EcoString::from("unicode-test"),
             description: EcoString::new(),
             usage: EcoString::new(),
             options: eco_vec![opt],
+            positionals: eco_vec![],
             subcommands: eco_vec![],
             version: EcoString::new(),
+            metadata: None,
         };
 
         // All generators should handle unicode without panicking
@@ -367,14 +379,20 @@ proptest! {
             names: eco_vec![OptName::new(EcoString::from("--long-desc"), OptNameType::LongType)],
             argument: EcoString::new(),
             description: EcoString::from(desc),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         };
         let cmd = Command {
             name: EcoString::from("long-test"),
             description: EcoString::new(),
             usage: EcoString::new(),
             options: eco_vec![opt],
+            positionals: eco_vec![],
             subcommands: eco_vec![],
             version: EcoString::new(),
+            metadata: None,
         };
 
         // Should handle long descriptions without issues
@@ -389,6 +407,10 @@ proptest! {
                 names: eco_vec![OptName::new(EcoString::from(format!("--opt-{}", i)), OptNameType::LongType)],
                 argument: EcoString::new(),
                 description: EcoString::from(format!("Option {}", i)),
+                multiple: false,
+                toggle: false,
+                optional_argument: false,
+                value_range: None,
             })
             .collect();
 
@@ -397,8 +419,10 @@ proptest! {
             description: EcoString::new(),
             usage: EcoString::new(),
             options,
+            positionals: eco_vec![],
             subcommands: eco_vec![],
             version: EcoString::new(),
+            metadata: None,
         };
 
         // Should handle many options
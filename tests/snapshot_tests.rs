@@ -1,10 +1,41 @@
 use clap::Parser as ClapParser;
 use d2o::types::OptNameType;
 use d2o::{
-    BashGenerator, Cli, Command, ElvishGenerator, FishGenerator, NushellGenerator, Opt, OptName,
-    Parser as D2oParser, ZshGenerator,
+    BashGenerator, CarapaceGenerator, Cli, Command, ElvishGenerator, FishGenerator,
+    GeneratorOptions, NushellGenerator, Opt, OptName, Parser as D2oParser, Positional,
+    PowerShellGenerator, ZshGenerator,
 };
-use ecow::{EcoString, eco_vec};
+use ecow::{EcoString, EcoVec, eco_vec};
+
+/// An option with the given names, argument and description.
+fn opt(names: &[(&str, OptNameType)], argument: &str, description: &str) -> Opt {
+    Opt {
+        names: names
+            .iter()
+            .map(|(name, opt_type)| OptName::new(EcoString::from(*name), *opt_type))
+            .collect(),
+        argument: EcoString::from(argument),
+        description: EcoString::from(description),
+        multiple: false,
+        toggle: false,
+        optional_argument: false,
+        value_range: None,
+    }
+}
+
+/// A command described as "Test command" with the given usage and options.
+fn command(name: &str, usage: &str, options: EcoVec<Opt>) -> Command {
+    let mut cmd = Command::new(EcoString::from(name));
+    cmd.description = EcoString::from("Test command");
+    cmd.usage = EcoString::from(usage);
+    cmd.options = options;
+    cmd
+}
+
+const VERBOSE: &[(&str, OptNameType)] = &[
+    ("-v", OptNameType::ShortType),
+    ("--verbose", OptNameType::LongType),
+];
 
 #[test]
 fn test_parse_ls_help_snapshot() {
@@ -32,15 +63,84 @@ fn test_zsh_generator_with_descriptions_snapshot() {
             ],
             argument: EcoString::new(),
             description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = ZshGenerator::generate(&cmd);
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_zsh_generator_argument_actions_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS]",
+        eco_vec![
+            opt(
+                &[("--output", OptNameType::LongType)],
+                "FILE",
+                "Write output to FILE"
+            ),
+            opt(
+                &[("--level", OptNameType::LongType)],
+                "LEVEL",
+                "Compression level"
+            ),
+            opt(&[("--quiet", OptNameType::LongType)], "", "Suppress output"),
+        ],
+    );
+
+    let output = ZshGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_powershell_generator_with_descriptions_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS]",
+        eco_vec![
+            opt(VERBOSE, "", "Enable verbose mode"),
+            opt(
+                &[("--", OptNameType::DoubleDashAlone)],
+                "",
+                "End of options"
+            ),
+        ],
+    );
+
+    let output = PowerShellGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_zsh_generator_mixed_descriptions_snapshot() {
+    let mut cmd = Command::new(EcoString::from("test"));
+    cmd.options = eco_vec![
+        opt(
+            &[("--verbose", OptNameType::LongType)],
+            "",
+            "Enable verbose mode"
+        ),
+        opt(&[("--quiet", OptNameType::LongType)], "", ""),
+        Opt {
+            optional_argument: true,
+            ..opt(&[("--color", OptNameType::LongType)], "WHEN", "")
+        },
+    ];
+
+    let output = ZshGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
 #[test]
 fn test_parse_docker_help_snapshot() {
     let docker_help = r#"
@@ -67,13 +167,28 @@ fn test_elvish_generator_snapshot() {
             ],
             argument: EcoString::new(),
             description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = ElvishGenerator::generate(&cmd);
+    assert!(output.contains("set edit:completion:arg-completer[test] = {|@words|"));
     insta::assert_snapshot!(output);
+
+    let options = GeneratorOptions {
+        elvish_module: Some(EcoString::from("test")),
+        ..Default::default()
+    };
+    let module = ElvishGenerator::generate_with_options(&cmd, &options);
+    assert!(module.contains("\nfn test-completions {|@words|\n"));
+    insta::assert_snapshot!("elvish_generator_module_snapshot", module);
 }
 
 #[test]
@@ -89,9 +204,15 @@ fn test_nushell_generator_snapshot() {
             ],
             argument: EcoString::new(),
             description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = NushellGenerator::generate(&cmd);
@@ -125,6 +246,17 @@ fn test_cli_effective_format_and_helpers() {
     assert_eq!(cli_json.effective_format(), "json");
 }
 
+#[test]
+fn test_cli_completions_with_input_acts_as_format() {
+    let cli = Cli::try_parse_from(["d2o", "--completions", "zsh"]).unwrap();
+    assert!(!cli.has_input_source());
+    assert_eq!(cli.effective_format(), "native");
+
+    let cli = Cli::try_parse_from(["d2o", "--command", "ls", "--completions", "zsh"]).unwrap();
+    assert!(cli.has_input_source());
+    assert_eq!(cli.effective_format(), "zsh");
+}
+
 #[test]
 fn test_bash_generator_snapshot() {
     let cmd = Command {
@@ -138,9 +270,15 @@ fn test_bash_generator_snapshot() {
             ],
             argument: EcoString::new(),
             description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = BashGenerator::generate(&cmd);
@@ -160,15 +298,63 @@ fn test_bash_generator_compat_snapshot() {
             ],
             argument: EcoString::new(),
             description: EcoString::from("Enable verbose mode"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = BashGenerator::generate_with_compat(&cmd, true);
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_bash_generator_nospace_toggle_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS]",
+        eco_vec![opt(
+            &[("--verbose", OptNameType::LongType)],
+            "",
+            "Enable verbose mode"
+        )],
+    );
+
+    let with_nospace = BashGenerator::generate(&cmd);
+    assert!(with_nospace.ends_with("complete -o bashdefault -o default -o nospace -F _test test"));
+
+    let options = GeneratorOptions {
+        bash_nospace: false,
+        ..Default::default()
+    };
+    let without_nospace = BashGenerator::generate_with_options(&cmd, &options);
+    assert!(!without_nospace.contains("nospace"));
+    insta::assert_snapshot!(without_nospace);
+}
+
+#[test]
+fn test_bash_generator_function_prefix_snapshot() {
+    let cmd = command(
+        "mytool",
+        "mytool [OPTIONS]",
+        eco_vec![opt(VERBOSE, "", "Enable verbose mode")],
+    );
+
+    let options = GeneratorOptions {
+        bash_function_prefix: Some(EcoString::from("__d2o_")),
+        ..Default::default()
+    };
+    let output = BashGenerator::generate_with_options(&cmd, &options);
+    assert!(output.starts_with("__d2o_mytool()"));
+    assert!(output.ends_with("-F __d2o_mytool mytool"));
+    insta::assert_snapshot!(output);
+}
+
 #[test]
 fn test_fish_generator_snapshot() {
     let cmd = Command {
@@ -182,11 +368,205 @@ fn test_fish_generator_snapshot() {
             ],
             argument: EcoString::from("FILE"),
             description: EcoString::from("Enable verbose mode using a file"),
+            multiple: false,
+            toggle: false,
+            optional_argument: false,
+            value_range: None,
         }],
+        positionals: eco_vec![],
         subcommands: eco_vec![],
         version: EcoString::new(),
+        metadata: None,
     };
 
     let output = FishGenerator::generate(&cmd);
     insta::assert_snapshot!(output);
 }
+
+#[test]
+fn test_powershell_generator_subcommand_sets_snapshot() {
+    let sub = |name: &str, description: &str, opt: Opt| {
+        let mut sub = Command::new(EcoString::from(name));
+        sub.description = EcoString::from(description);
+        sub.options = eco_vec![opt];
+        sub
+    };
+
+    let mut cmd = command(
+        "test",
+        "test [OPTIONS] <COMMAND>",
+        eco_vec![opt(VERBOSE, "", "Enable verbose mode")],
+    );
+    cmd.subcommands = eco_vec![
+        sub(
+            "add",
+            "Add a file",
+            opt(
+                &[
+                    ("-f", OptNameType::ShortType),
+                    ("--force", OptNameType::LongType)
+                ],
+                "",
+                "Don't ask"
+            )
+        ),
+        sub(
+            "remove",
+            "Remove a file",
+            opt(
+                &[
+                    ("-r", OptNameType::ShortType),
+                    ("--recursive", OptNameType::LongType)
+                ],
+                "",
+                "Recurse into dirs"
+            )
+        ),
+    ];
+
+    let output = PowerShellGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_bash_generator_passthrough_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS] [--] [ARGS]...",
+        eco_vec![
+            opt(
+                &[("--verbose", OptNameType::LongType)],
+                "",
+                "Enable verbose mode"
+            ),
+            opt(
+                &[("--", OptNameType::DoubleDashAlone)],
+                "",
+                "Pass the remaining arguments through"
+            ),
+        ],
+    );
+
+    let output = BashGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_carapace_generator_snapshot() {
+    let mut sub = Command::new(EcoString::from("add"));
+    sub.description = EcoString::from("Add a file");
+    sub.options = eco_vec![opt(
+        &[
+            ("-f", OptNameType::ShortType),
+            ("--force", OptNameType::LongType)
+        ],
+        "",
+        "Don't ask"
+    )];
+
+    let mut cmd = command(
+        "test",
+        "test [OPTIONS] <COMMAND>",
+        eco_vec![
+            opt(VERBOSE, "", "Enable verbose mode"),
+            opt(
+                &[("--output", OptNameType::LongType)],
+                "FILE",
+                "Write \"output\" to FILE"
+            ),
+        ],
+    );
+    cmd.subcommands = eco_vec![sub];
+
+    let output = CarapaceGenerator::generate(&cmd);
+    insta::assert_snapshot!("carapace", output);
+}
+
+#[test]
+fn test_fish_generator_positionals_snapshot() {
+    let mut cmd = Command::new(EcoString::from("test"));
+    cmd.options = eco_vec![opt(
+        &[("--verbose", OptNameType::LongType)],
+        "",
+        "Enable verbose mode"
+    )];
+    cmd.positionals = eco_vec![
+        Positional {
+            name: EcoString::from("<FILE>"),
+            description: EcoString::from("The input file"),
+        },
+        Positional {
+            name: EcoString::from("{start,stop}"),
+            description: EcoString::from("Action to run"),
+        },
+        Positional {
+            name: EcoString::from("<NAME>"),
+            description: EcoString::from("Not completable"),
+        },
+    ];
+
+    let output = FishGenerator::generate(&cmd);
+    assert!(output.contains("complete -c test -n '__fish_use_subcommand' -F -d 'The input file'"));
+    assert!(!output.contains("Not completable"));
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_fish_generator_preserve_order_snapshot() {
+    let mut cmd = Command::new(EcoString::from("test"));
+    cmd.options = D2oParser::parse_line(
+        "  -z, --zebra      Zebra mode\n  -a, --alpha      Alpha mode\n  -m, --middle      Middle mode",
+    );
+    cmd.positionals = eco_vec![Positional {
+        name: EcoString::from("{start,stop}"),
+        description: EcoString::from("Action to run"),
+    }];
+
+    let default = FishGenerator::generate(&cmd);
+    let ordered = FishGenerator::generate_with_options(
+        &cmd,
+        &GeneratorOptions {
+            preserve_order: true,
+            ..Default::default()
+        },
+    );
+    assert!(!default.contains(" -k "));
+    insta::assert_snapshot!("fish_generator_default_order", default);
+    insta::assert_snapshot!("fish_generator_preserve_order", ordered);
+}
+
+#[test]
+fn test_fish_generator_negatable_flag_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS]",
+        D2oParser::parse_line("  --[no-]color      Use colors in the output"),
+    );
+
+    let output = FishGenerator::generate(&cmd);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_zsh_generator_escapes_argument_specs_snapshot() {
+    let cmd = command(
+        "test",
+        "test [OPTIONS]",
+        eco_vec![
+            opt(
+                &[("--listen", OptNameType::LongType)],
+                "HOST:PORT",
+                "Listen address"
+            ),
+            opt(
+                &[("--config", OptNameType::LongType)],
+                "[FILE]",
+                "Read the user's config"
+            ),
+        ],
+    );
+
+    let output = ZshGenerator::generate(&cmd);
+    assert!(output.contains("'--listen[HOST\\:PORT Listen address]:HOST\\:PORT:( )'"));
+    insta::assert_snapshot!(output);
+}